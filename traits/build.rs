@@ -0,0 +1,19 @@
+// Generates the C header for the functions in src/ffi.rs into OUT_DIR; building never
+// touches the source tree, which matters when `traits` is only a dependency. A copy is
+// checked in as c/shapes.h for C users without a Rust toolchain, and the
+// `checked_in_header_is_up_to_date` test fails when that copy goes stale.
+fn main() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("cbindgen.toml is valid");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/ffi.rs", crate_dir))
+        .generate()
+        .expect("src/ffi.rs can be turned into a C header")
+        .write_to_file(format!("{}/shapes.h", out_dir));
+}
@@ -0,0 +1,60 @@
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#ifndef TRAITS_SHAPES_H
+#define TRAITS_SHAPES_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum {
+  SHAPES_STATUS_OK = 0,
+  SHAPES_STATUS_NULL_POINTER = 1,
+  SHAPES_STATUS_NEGATIVE_DIMENSION = 2,
+  SHAPES_STATUS_OVERFLOW = 3,
+  SHAPES_STATUS_PANIC = 4,
+} ShapesStatus;
+
+typedef struct ShapesShape ShapesShape;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a square. On success `*out` receives a handle to free with `shapes_shape_free`.
+ShapesStatus shapes_square_new(int32_t side, ShapesShape **out);
+
+// Creates a rectangle. On success `*out` receives a handle to free with
+// `shapes_shape_free`.
+ShapesStatus shapes_rectangle_new(int32_t length, int32_t breadth, ShapesShape **out);
+
+// Releases a shape. NULL is ignored.
+//
+// # Safety
+// `shape` must be NULL or a handle from this library that wasn't freed yet.
+void shapes_shape_free(ShapesShape *shape);
+
+// Writes the area of the shape to `*out`, or returns `SHAPES_STATUS_OVERFLOW` if it
+// doesn't fit in an `int32_t`.
+//
+// # Safety
+// `shape` must be NULL or a live handle from this library.
+ShapesStatus shapes_shape_area(const ShapesShape *shape, double *out);
+
+// Writes the perimeter of the shape to `*out`, or returns `SHAPES_STATUS_OVERFLOW` if it
+// doesn't fit in an `int32_t`.
+//
+// # Safety
+// `shape` must be NULL or a live handle from this library.
+ShapesStatus shapes_shape_perimeter(const ShapesShape *shape, double *out);
+
+// A static, NUL-terminated description of a status code. Never NULL. Takes a plain
+// integer so that any value coming from C is valid; unknown codes get "unknown status".
+const char *shapes_status_message(uint32_t status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TRAITS_SHAPES_H */
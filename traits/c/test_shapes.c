@@ -0,0 +1,59 @@
+/* Exercises the C API from C. Built and run by the `c_harness_passes` test in
+ * src/ffi.rs; exits with a non-zero status on the first failed check. */
+#include <stdio.h>
+#include <string.h>
+
+#include "shapes.h"
+
+#define CHECK(condition)                                                  \
+    do {                                                                  \
+        if (!(condition)) {                                               \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,        \
+                    __LINE__, #condition);                                \
+            return 1;                                                     \
+        }                                                                 \
+    } while (0)
+
+int main(void) {
+    ShapesShape *square = NULL;
+    ShapesShape *rectangle = NULL;
+    double area = 0.0;
+    double perimeter = 0.0;
+
+    CHECK(shapes_square_new(4, &square) == SHAPES_STATUS_OK);
+    CHECK(square != NULL);
+    CHECK(shapes_shape_area(square, &area) == SHAPES_STATUS_OK);
+    CHECK(shapes_shape_perimeter(square, &perimeter) == SHAPES_STATUS_OK);
+    CHECK(area == 16.0 && perimeter == 16.0);
+
+    CHECK(shapes_rectangle_new(8, 6, &rectangle) == SHAPES_STATUS_OK);
+    CHECK(shapes_shape_area(rectangle, &area) == SHAPES_STATUS_OK);
+    CHECK(shapes_shape_perimeter(rectangle, &perimeter) == SHAPES_STATUS_OK);
+    CHECK(area == 48.0 && perimeter == 28.0);
+
+    /* Errors come back as status codes and leave the out parameters alone. */
+    ShapesShape *invalid = NULL;
+    CHECK(shapes_rectangle_new(8, -6, &invalid) == SHAPES_STATUS_NEGATIVE_DIMENSION);
+    CHECK(invalid == NULL);
+    CHECK(shapes_square_new(1, NULL) == SHAPES_STATUS_NULL_POINTER);
+    CHECK(shapes_shape_area(NULL, &area) == SHAPES_STATUS_NULL_POINTER);
+    CHECK(shapes_shape_area(square, NULL) == SHAPES_STATUS_NULL_POINTER);
+    CHECK(area == 48.0);
+    CHECK(strcmp(shapes_status_message(SHAPES_STATUS_NEGATIVE_DIMENSION),
+                 "negative dimension") == 0);
+    CHECK(strcmp(shapes_status_message(42), "unknown status") == 0);
+
+    /* Measurements that don't fit in an int32_t. */
+    ShapesShape *huge = NULL;
+    CHECK(shapes_square_new(50000, &huge) == SHAPES_STATUS_OK);
+    CHECK(shapes_shape_area(huge, &area) == SHAPES_STATUS_OVERFLOW);
+    CHECK(area == 48.0);
+    shapes_shape_free(huge);
+
+    shapes_shape_free(square);
+    shapes_shape_free(rectangle);
+    shapes_shape_free(NULL);
+
+    puts("all C API checks passed");
+    return 0;
+}
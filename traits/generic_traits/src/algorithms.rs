@@ -0,0 +1,465 @@
+// Computational geometry over slices of points. A slice stands for a polygon by listing
+// its vertices in order, the last one joined back to the first (as in `Polygon`).
+//
+// Every decision (which side of a line a point is on, which of two distances is smaller)
+// is made with exact products in the coordinates' `Wide` type, so integer inputs never
+// give wrong answers through rounding or overflow. That holds for any `Coord` type.
+use std::cmp::Ordering;
+
+use crate::num::{Coord, Num};
+use crate::point::Point;
+
+// Where a point is relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    // On an edge or vertex.
+    Boundary,
+    Outside,
+}
+
+// Sorts points by x, then y. Coordinates must not be NaN.
+fn by_x_then_y<T: Coord>(a: &Point<T>, b: &Point<T>) -> Ordering {
+    a.x.partial_cmp(&b.x)
+        .and_then(|order| Some(order.then(a.y.partial_cmp(&b.y)?)))
+        .expect("coordinates are not NaN")
+}
+
+// Positive if `o`, `a`, `b` turn counter-clockwise, negative if clockwise, zero if they
+// are on one line.
+fn turn<T: Coord>(o: Point<T>, a: Point<T>, b: Point<T>) -> T::Wide {
+    let o = o.widen();
+    (a.widen() - o).cross(b.widen() - o)
+}
+
+// The polygon's edges as pairs of consecutive vertices, closing edge included.
+fn edges<T: Coord>(polygon: &[Point<T>]) -> impl Iterator<Item = (Point<T>, Point<T>)> + '_ {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+// The vertices of the smallest convex polygon containing all the points, counter-clockwise
+// from the lowest leftmost point (Andrew's monotone chain). Points in the middle of a hull
+// edge are not vertices. Fewer than three distinct points are returned as they are.
+pub fn convex_hull<T: Coord>(points: &[Point<T>]) -> Vec<Point<T>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(by_x_then_y);
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    // The lower hull from left to right and the upper hull back, keeping only left turns.
+    let mut hull: Vec<Point<T>> = Vec::with_capacity(sorted.len() + 1);
+    for pass in [sorted.clone(), sorted.into_iter().rev().collect()] {
+        let chain_start = hull.len();
+        for point in pass {
+            while hull.len() >= chain_start + 2
+                && turn(hull[hull.len() - 2], hull[hull.len() - 1], point) <= T::Wide::ZERO
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // Each chain ends where the other one starts.
+        hull.pop();
+    }
+    hull
+}
+
+// The two points closest to each other, `None` for fewer than two points.
+pub fn closest_pair<T: Coord>(points: &[Point<T>]) -> Option<(Point<T>, Point<T>)> {
+    if points.len() < 2 {
+        return None;
+    }
+    let mut sorted = points.to_vec();
+    sorted.sort_by(by_x_then_y);
+    let (_, a, b) = closest_in(&sorted);
+    Some((a, b))
+}
+
+// Divide and conquer over points sorted by x: the closest pair is in the left half, in the
+// right half, or straddles the dividing line within the best distance so far.
+fn closest_in<T: Coord>(points: &[Point<T>]) -> (T::Wide, Point<T>, Point<T>) {
+    if points.len() <= 3 {
+        let mut best = (
+            points[0].widen().distance_squared(points[1].widen()),
+            points[0],
+            points[1],
+        );
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                let distance = points[i].widen().distance_squared(points[j].widen());
+                if distance < best.0 {
+                    best = (distance, points[i], points[j]);
+                }
+            }
+        }
+        return best;
+    }
+
+    let (left, right) = points.split_at(points.len() / 2);
+    let (from_left, from_right) = (closest_in(left), closest_in(right));
+    let mut best = if from_left.0 <= from_right.0 {
+        from_left
+    } else {
+        from_right
+    };
+
+    let middle_x = right[0].x.widen();
+    let mut strip: Vec<Point<T>> = points
+        .iter()
+        .copied()
+        .filter(|point| {
+            let dx = point.x.widen() - middle_x;
+            dx * dx < best.0
+        })
+        .collect();
+    strip.sort_by(|a, b| a.y.partial_cmp(&b.y).expect("coordinates are not NaN"));
+    for (i, &a) in strip.iter().enumerate() {
+        for &b in &strip[i + 1..] {
+            let dy = b.y.widen() - a.y.widen();
+            if dy * dy >= best.0 {
+                break;
+            }
+            let distance = a.widen().distance_squared(b.widen());
+            if distance < best.0 {
+                best = (distance, a, b);
+            }
+        }
+    }
+    best
+}
+
+// Whether `point` is inside, on the boundary of or outside the polygon, by counting the
+// edges a ray from the point to the right crosses. Self-intersecting polygons follow the
+// even-odd rule.
+pub fn point_in_polygon<T: Coord>(point: Point<T>, polygon: &[Point<T>]) -> Location {
+    let zero = T::Wide::ZERO;
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        let side = turn(a, b, point);
+        if side == zero && between(point.x, a.x, b.x) && between(point.y, a.y, b.y) {
+            return Location::Boundary;
+        }
+        // The edge spans the point's height (counting its lower end but not its upper end,
+        // so a ray through a vertex counts once) and passes to the right of the point.
+        if (a.y > point.y) != (b.y > point.y) && (side > zero) == (b.y > a.y) {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+// Whether `value` is in the range from `a` to `b` in either order, ends included.
+fn between<T: Coord>(value: T, a: T, b: T) -> bool {
+    (a <= value && value <= b) || (b <= value && value <= a)
+}
+
+// Twice the polygon's area, positive for counter-clockwise polygons (the shoelace
+// formula). Exact for integer vertices.
+pub fn signed_area_doubled<T: Coord>(polygon: &[Point<T>]) -> T::Wide {
+    let mut sum = T::Wide::ZERO;
+    for (a, b) in edges(polygon) {
+        sum += a.widen().cross(b.widen());
+    }
+    sum
+}
+
+pub fn polygon_area<T: Coord>(polygon: &[Point<T>]) -> f64 {
+    signed_area_doubled(polygon).to_f64().abs() / 2.0
+}
+
+// The centre of mass of the polygon's area, `None` if it has no area.
+pub fn polygon_centroid<T: Coord>(polygon: &[Point<T>]) -> Option<Point<f64>> {
+    let area_doubled = signed_area_doubled(polygon);
+    if area_doubled == T::Wide::ZERO {
+        return None;
+    }
+    let mut sum = Point::new(T::Wide::ZERO, T::Wide::ZERO);
+    for (a, b) in edges(polygon) {
+        let (a, b) = (a.widen(), b.widen());
+        sum += (a + b) * a.cross(b);
+    }
+    let scale = 3.0 * area_doubled.to_f64();
+    Some(Point::new(sum.x.to_f64() / scale, sum.y.to_f64() / scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Polygon;
+
+    // A small xorshift generator, so the tests are random but repeatable.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, bound: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % bound
+        }
+
+        fn coordinate(&mut self, limit: i32) -> i32 {
+            self.below(2 * limit as u64 + 1) as i32 - limit
+        }
+
+        fn points(&mut self, count: usize, limit: i32) -> Vec<Point> {
+            (0..count)
+                .map(|_| Point::new(self.coordinate(limit), self.coordinate(limit)))
+                .collect()
+        }
+    }
+
+    fn p(x: i32, y: i32) -> Point {
+        Point::new(x, y)
+    }
+
+    // Whether `point` is in the triangle or on its border. A flat triangle is the segment
+    // between its outermost corners.
+    fn in_triangle(point: Point, corners: [Point; 3]) -> bool {
+        let [a, b, c] = corners;
+        let sides = [turn(a, b, point), turn(b, c, point), turn(c, a, point)];
+        if turn(a, b, c) == 0 {
+            let xs = corners.map(|corner| corner.x);
+            let ys = corners.map(|corner| corner.y);
+            return sides == [0; 3]
+                && (xs.iter().min() <= Some(&point.x) && Some(&point.x) <= xs.iter().max())
+                && (ys.iter().min() <= Some(&point.y) && Some(&point.y) <= ys.iter().max());
+        }
+        sides.iter().all(|&side| side >= 0) || sides.iter().all(|&side| side <= 0)
+    }
+
+    // Brute force: a point is a hull vertex unless it lies in a triangle of (or on a
+    // segment between) other points.
+    fn hull_vertices(points: &[Point]) -> Vec<Point> {
+        let mut distinct = points.to_vec();
+        distinct.sort_by(by_x_then_y);
+        distinct.dedup();
+        let mut vertices = Vec::new();
+        for &candidate in &distinct {
+            let others: Vec<Point> = distinct
+                .iter()
+                .copied()
+                .filter(|&other| other != candidate)
+                .collect();
+            let mut covered = false;
+            for i in 0..others.len() {
+                for j in i..others.len() {
+                    for k in j..others.len() {
+                        covered |= in_triangle(candidate, [others[i], others[j], others[k]]);
+                    }
+                }
+            }
+            if !covered {
+                vertices.push(candidate);
+            }
+        }
+        vertices
+    }
+
+    #[test]
+    fn finds_convex_hulls() {
+        let points = [
+            p(0, 0),
+            p(4, 0),
+            p(2, 2),
+            p(4, 4),
+            p(0, 4),
+            p(2, 0),
+            p(1, 3),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![p(0, 0), p(4, 0), p(4, 4), p(0, 4)]
+        );
+        assert_eq!(
+            convex_hull(&[p(3, 3), p(0, 0), p(1, 1), p(3, 3)]),
+            vec![p(0, 0), p(3, 3)]
+        );
+        assert_eq!(convex_hull(&[p(1, 1), p(1, 1)]), vec![p(1, 1)]);
+        assert_eq!(convex_hull::<i32>(&[]), vec![]);
+    }
+
+    #[test]
+    fn convex_hull_matches_brute_force() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for round in 0..300 {
+            let points = rng.points(1 + round % 15, 6);
+            let hull = convex_hull(&points);
+
+            let mut vertices = hull.clone();
+            vertices.sort_by(by_x_then_y);
+            assert_eq!(vertices, hull_vertices(&points), "points {:?}", points);
+            if hull.len() >= 3 {
+                for (a, b) in edges(&hull) {
+                    assert!(points.iter().all(|&point| turn(a, b, point) >= 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn closest_pair_matches_brute_force() {
+        assert_eq!(closest_pair(&[p(0, 0)]), None);
+        assert_eq!(
+            closest_pair(&[p(0, 0), p(10, 10), p(3, 4), p(9, 9)]),
+            Some((p(9, 9), p(10, 10)))
+        );
+
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for round in 0..300 {
+            let points = rng.points(2 + round % 40, 50);
+            let (a, b) = closest_pair(&points).unwrap();
+
+            let mut brute = i32::MAX;
+            for i in 0..points.len() {
+                for j in i + 1..points.len() {
+                    brute = brute.min(points[i].distance_squared(points[j]));
+                }
+            }
+            assert_eq!(a.distance_squared(b), brute, "points {:?}", points);
+            assert!(points.contains(&a) && points.contains(&b));
+        }
+    }
+
+    #[test]
+    fn locates_points_in_polygons() {
+        // An L shape.
+        let l_shape = [p(0, 0), p(4, 0), p(4, 2), p(2, 2), p(2, 4), p(0, 4)];
+
+        assert_eq!(point_in_polygon(p(1, 1), &l_shape), Location::Inside);
+        assert_eq!(point_in_polygon(p(1, 3), &l_shape), Location::Inside);
+        assert_eq!(point_in_polygon(p(3, 3), &l_shape), Location::Outside);
+        assert_eq!(point_in_polygon(p(2, 3), &l_shape), Location::Boundary);
+        assert_eq!(point_in_polygon(p(4, 0), &l_shape), Location::Boundary);
+        // A ray through the vertex at (2, 2) and along the edge at y = 2.
+        assert_eq!(point_in_polygon(p(1, 2), &l_shape), Location::Inside);
+        assert_eq!(point_in_polygon(p(-1, 2), &l_shape), Location::Outside);
+        assert_eq!(point_in_polygon(p(5, 0), &l_shape), Location::Outside);
+    }
+
+    // Random polygons that are star-shaped around the origin: one vertex per angle step,
+    // at a random distance.
+    fn star_polygon(rng: &mut Rng, corners: usize) -> Vec<Point> {
+        (0..corners)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / corners as f64;
+                let radius = 4.0 + rng.below(12) as f64;
+                p(
+                    (radius * angle.cos()).round() as i32,
+                    (radius * angle.sin()).round() as i32,
+                )
+            })
+            .collect()
+    }
+
+    // Brute force: on an edge if on one of the segments, otherwise inside if the polygon
+    // winds around the point, adding up the angles it turns through seen from the point.
+    fn locate_by_winding(point: Point, polygon: &[Point]) -> Location {
+        let mut total = 0.0;
+        for (a, b) in edges(polygon) {
+            let (u, v) = (a - point, b - point);
+            if u.cross(v) == 0 && u.dot(v) <= 0 {
+                return Location::Boundary;
+            }
+            total += (u.cross(v) as f64).atan2(u.dot(v) as f64);
+        }
+        if total.abs() > std::f64::consts::PI {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    #[test]
+    fn point_in_polygon_matches_winding_numbers() {
+        let mut rng = Rng(0xdead_beef_cafe_f00d);
+        let mut checked = 0;
+        for round in 0..100 {
+            let polygon = star_polygon(&mut rng, 3 + round % 10);
+            if Polygon::new(polygon.clone()).is_self_intersecting() {
+                continue;
+            }
+            checked += 1;
+            for x in -16..=16 {
+                for y in -16..=16 {
+                    assert_eq!(
+                        point_in_polygon(p(x, y), &polygon),
+                        locate_by_winding(p(x, y), &polygon),
+                        "point {:?} in {:?}",
+                        p(x, y),
+                        polygon
+                    );
+                }
+            }
+        }
+        assert!(checked > 50, "only {} usable polygons", checked);
+    }
+
+    #[test]
+    fn computes_areas_and_centroids() {
+        let l_shape = [p(0, 0), p(4, 0), p(4, 2), p(2, 2), p(2, 4), p(0, 4)];
+
+        assert_eq!(signed_area_doubled(&l_shape), 24);
+        assert_eq!(polygon_area(&l_shape), 12.0);
+        // Two 4 x 2 and 2 x 2 rectangles with centres (2, 1) and (1, 3).
+        let centroid = polygon_centroid(&l_shape).unwrap();
+        assert!((centroid.x - 5.0 / 3.0).abs() < 1e-12);
+        assert!((centroid.y - 5.0 / 3.0).abs() < 1e-12);
+        assert_eq!(polygon_centroid(&[p(0, 0), p(1, 1), p(2, 2)]), None);
+
+        let mut clockwise = l_shape.to_vec();
+        clockwise.reverse();
+        assert_eq!(polygon_area(&clockwise), 12.0);
+        assert_eq!(polygon_centroid(&clockwise), Some(centroid));
+    }
+
+    #[test]
+    fn areas_and_centroids_match_brute_force() {
+        let mut rng = Rng(0x0123_4567_89ab_cdef);
+        let mut checked = 0;
+        for round in 0..100 {
+            let polygon = star_polygon(&mut rng, 3 + round % 10);
+            if Polygon::new(polygon.clone()).is_self_intersecting() {
+                continue;
+            }
+            checked += 1;
+
+            // Pick's theorem: area = interior lattice points + boundary points / 2 - 1.
+            let (mut interior, mut boundary) = (0, 0);
+            for x in -16..=16 {
+                for y in -16..=16 {
+                    match locate_by_winding(p(x, y), &polygon) {
+                        Location::Inside => interior += 1,
+                        Location::Boundary => boundary += 1,
+                        Location::Outside => {}
+                    }
+                }
+            }
+            let area = polygon_area(&polygon);
+            assert_eq!(area, interior as f64 + boundary as f64 / 2.0 - 1.0);
+
+            // Fan triangulation from the first vertex: the centroid is the area-weighted
+            // mean of the triangles' centroids, using signed areas.
+            let (mut weighted, mut total) = (Point::new(0.0, 0.0), 0.0);
+            for pair in polygon[1..].windows(2) {
+                let triangle_area = turn(polygon[0], pair[0], pair[1]) as f64;
+                let triangle_centroid = (polygon[0] + pair[0] + pair[1]).to_f64() * (1.0 / 3.0);
+                weighted += triangle_centroid * triangle_area;
+                total += triangle_area;
+            }
+            let expected = weighted * (1.0 / total);
+            let centroid = polygon_centroid(&polygon).unwrap();
+            assert!(centroid.distance(expected) < 1e-9, "{:?}", polygon);
+        }
+        assert!(checked > 50, "only {} usable polygons", checked);
+    }
+}
@@ -0,0 +1,34 @@
+// Use `Associated`` traits (traits that use the `type` keyword) when there should be only a single
+// implementation of a trait per type. However, if there are many possible implementations of the
+// trait per type, then use `Generic` traits
+
+// Numeric types that points can be built from
+pub mod num;
+
+// Points as 2D vectors: operators, dot and cross products, lengths
+pub mod point;
+
+// Line segments between two points: measurements, intersections and clipping
+pub mod line;
+
+// Polylines and polygons built by chaining `Addition`
+pub mod path;
+
+// Bresenham, DDA and anti-aliased (Wu) rasterization of lines
+pub mod raster;
+
+// Convex hulls, closest pairs, point location, areas and centroids of point sets
+pub mod algorithms;
+
+pub use algorithms::{
+    closest_pair, convex_hull, point_in_polygon, polygon_area, polygon_centroid, Location,
+};
+pub use line::{Intersection, Line, Rect};
+pub use num::{Coord, Num, Ratio};
+pub use path::{Polygon, Polyline, Winding};
+pub use point::Point;
+pub use raster::{AntialiasedPixels, DdaPixels, Pixels};
+
+pub trait Addition<T, U> {
+    fn add(&self, rhs: T) -> U;
+}
@@ -0,0 +1,460 @@
+// Line segments, made by adding two points with `Addition`.
+//
+// Directions, squared lengths, intersections and clipping are exact: they only add and
+// multiply coordinates, in their `Wide` type so `Coord` coordinates can't overflow, and
+// return fractional points as `Ratio`s. Lengths, slopes and closest points involve square
+// roots or divisions and are `f64`.
+use std::cmp::Ordering;
+
+use crate::num::{Coord, Num, Ratio};
+use crate::point::Point;
+use crate::Addition;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line<T = i32> {
+    pub start: Point<T>,
+    pub end: Point<T>,
+}
+
+// How two segments meet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Intersection<T: Num> {
+    None,
+    // A single point, which need not have whole coordinates.
+    Point(Point<Ratio<T::Wide>>),
+    // Colinear segments sharing more than a point. The overlap runs the same way as the
+    // segment `intersection` was called on.
+    Overlap(Line<T>),
+}
+
+// An axis-aligned rectangle, border included, for clipping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect<T = i32> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T: Num> Rect<T> {
+    // The rectangle spanned by two opposite corners.
+    pub fn new(a: Point<T>, b: Point<T>) -> Rect<T> {
+        let (min_x, max_x) = if a.x <= b.x { (a.x, b.x) } else { (b.x, a.x) };
+        let (min_y, max_y) = if a.y <= b.y { (a.y, b.y) } else { (b.y, a.y) };
+        Rect {
+            min: Point::new(min_x, min_y),
+            max: Point::new(max_x, max_y),
+        }
+    }
+}
+
+// Cohen–Sutherland region codes: which sides of the rectangle a point is beyond.
+const INSIDE: u8 = 0;
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+const BELOW: u8 = 4;
+const ABOVE: u8 = 8;
+
+fn outcode<W: Num>(point: Point<Ratio<W>>, min: Point<W>, max: Point<W>) -> u8 {
+    let mut code = INSIDE;
+    if point.x.cmp_whole(min.x) == Ordering::Less {
+        code |= LEFT;
+    } else if point.x.cmp_whole(max.x) == Ordering::Greater {
+        code |= RIGHT;
+    }
+    if point.y.cmp_whole(min.y) == Ordering::Less {
+        code |= BELOW;
+    } else if point.y.cmp_whole(max.y) == Ordering::Greater {
+        code |= ABOVE;
+    }
+    code
+}
+
+// The point at height `y` on the line through `start` with direction `d` (`d.y != 0`).
+fn at_y<W: Num>(start: Point<W>, d: Point<W>, y: W) -> Point<Ratio<W>> {
+    Point {
+        x: Ratio::new(start.x * d.y + d.x * (y - start.y), d.y),
+        y: Ratio::whole(y),
+    }
+}
+
+// The point at `x` on the line through `start` with direction `d` (`d.x != 0`).
+fn at_x<W: Num>(start: Point<W>, d: Point<W>, x: W) -> Point<Ratio<W>> {
+    Point {
+        x: Ratio::whole(x),
+        y: Ratio::new(start.y * d.x + d.y * (x - start.x), d.x),
+    }
+}
+
+// The pair ordered by key.
+fn ordered<K: PartialOrd, V>(a: (K, V), b: (K, V)) -> ((K, V), (K, V)) {
+    if a.0 <= b.0 {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+impl<T: Coord> Line<T> {
+    pub fn new(start: Point<T>, end: Point<T>) -> Line<T> {
+        Line { start, end }
+    }
+
+    // The vector from `start` to `end`, in `Wide` so that it fits even for segments
+    // spanning the whole range of `T`.
+    pub fn direction(self) -> Point<T::Wide> {
+        self.end.widen() - self.start.widen()
+    }
+
+    pub fn length_squared(self) -> T::Wide {
+        self.direction().magnitude_squared()
+    }
+
+    pub fn length(self) -> f64 {
+        self.start.distance(self.end)
+    }
+
+    pub fn midpoint(self) -> Point<f64> {
+        (self.start.to_f64() + self.end.to_f64()) * 0.5
+    }
+
+    // dy / dx, `None` for vertical segments (and single points).
+    pub fn slope(self) -> Option<f64> {
+        let d = self.direction();
+        if d.x == T::Wide::ZERO {
+            return None;
+        }
+        Some(d.y.to_f64() / d.x.to_f64())
+    }
+
+    // The point of the segment nearest to `point`.
+    pub fn closest_point(self, point: Point<T>) -> Point<f64> {
+        let start = self.start.to_f64();
+        let d = self.direction().to_f64();
+        let length_squared = d.magnitude_squared();
+        if length_squared == 0.0 {
+            return start;
+        }
+        let t = ((point.to_f64() - start).dot(d) / length_squared).clamp(0.0, 1.0);
+        start + d * t
+    }
+
+    // Solves start + t * r = other.start + u * s for 0 <= t, u <= 1, with t and u kept as
+    // fractions over r x s.
+    pub fn intersection(self, other: Line<T>) -> Intersection<T> {
+        let zero = T::Wide::ZERO;
+        let p = self.start.widen();
+        let r = self.end.widen() - p;
+        let q = other.start.widen();
+        let s = other.end.widen() - q;
+
+        let denominator = r.cross(s);
+        if denominator == zero {
+            return self.parallel_intersection(other);
+        }
+        let qp = q - p;
+        let (mut t, mut u, mut denominator) = (qp.cross(s), qp.cross(r), denominator);
+        if denominator < zero {
+            (t, u, denominator) = (-t, -u, -denominator);
+        }
+        if t < zero || t > denominator || u < zero || u > denominator {
+            return Intersection::None;
+        }
+        Intersection::Point(Point {
+            x: Ratio::new(p.x * denominator + r.x * t, denominator),
+            y: Ratio::new(p.y * denominator + r.y * t, denominator),
+        })
+    }
+
+    // `intersection` for segments with parallel directions, either of which may also be a
+    // single point.
+    fn parallel_intersection(self, other: Line<T>) -> Intersection<T> {
+        let zero = T::Wide::ZERO;
+        let a = self.start.widen();
+        let along = if self.start != self.end {
+            self.end.widen() - a
+        } else {
+            other.end.widen() - other.start.widen()
+        };
+        if along == Point::new(zero, zero) {
+            return if self.start == other.start {
+                Intersection::Point(Point::whole(a))
+            } else {
+                Intersection::None
+            };
+        }
+        let colinear = [self.end, other.start, other.end]
+            .iter()
+            .all(|point| (point.widen() - a).cross(along) == zero);
+        if !colinear {
+            return Intersection::None;
+        }
+
+        // Both segments as intervals of positions along the common line.
+        let position = |point: Point<T>| ((point.widen() - a).dot(along), point);
+        let (min1, max1) = ordered(position(self.start), position(self.end));
+        let (min2, max2) = ordered(position(other.start), position(other.end));
+        let low = if min1.0 >= min2.0 { min1 } else { min2 };
+        let high = if max1.0 <= max2.0 { max1 } else { max2 };
+        match low.0.partial_cmp(&high.0) {
+            Some(Ordering::Less) => Intersection::Overlap(Line::new(low.1, high.1)),
+            Some(Ordering::Equal) => Intersection::Point(Point::whole(low.1.widen())),
+            _ => Intersection::None,
+        }
+    }
+
+    // Cohen–Sutherland clipping: the part of the segment inside `rect`, if any. `None`
+    // as well if a coordinate is infinite or NaN.
+    pub fn clip(self, rect: Rect<T>) -> Option<Line<Ratio<T::Wide>>> {
+        let corners = [self.start, self.end, rect.min, rect.max];
+        if !corners
+            .iter()
+            .all(|corner| corner.x.to_f64().is_finite() && corner.y.to_f64().is_finite())
+        {
+            return None;
+        }
+        let (min, max) = (rect.min.widen(), rect.max.widen());
+        let start = self.start.widen();
+        let d = self.end.widen() - start;
+        let mut ends = [Point::whole(start), Point::whole(self.end.widen())];
+        let mut codes = ends.map(|end| outcode(end, min, max));
+        loop {
+            if codes[0] | codes[1] == INSIDE {
+                return Some(Line {
+                    start: ends[0],
+                    end: ends[1],
+                });
+            }
+            if codes[0] & codes[1] != INSIDE {
+                return None;
+            }
+            // Move an outside end onto the border it is beyond. The new point is computed
+            // from the original segment, so it stays exact.
+            let i = if codes[0] != INSIDE { 0 } else { 1 };
+            ends[i] = if codes[i] & ABOVE != 0 {
+                at_y(start, d, max.y)
+            } else if codes[i] & BELOW != 0 {
+                at_y(start, d, min.y)
+            } else if codes[i] & RIGHT != 0 {
+                at_x(start, d, max.x)
+            } else {
+                at_x(start, d, min.x)
+            };
+            codes[i] = outcode(ends[i], min, max);
+        }
+    }
+}
+
+// Exact segments with fractional end points, as returned by `Line::clip`.
+impl<T: Num> Line<Ratio<T>> {
+    // The segment if all its coordinates are whole numbers that fit in `N`.
+    pub fn to_whole<N: Num<Wide = T>>(self) -> Option<Line<N>> {
+        Some(Line {
+            start: self.start.to_whole()?,
+            end: self.end.to_whole()?,
+        })
+    }
+
+    pub fn to_f64(self) -> Line<f64> {
+        Line {
+            start: self.start.to_f64(),
+            end: self.end.to_f64(),
+        }
+    }
+}
+
+impl<T: Coord> Addition<Point<T>, Line<T>> for Point<T> {
+    fn add(&self, rhs: Point<T>) -> Line<T> {
+        Line {
+            start: self.to_owned(),
+            end: rhs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(x1: i32, y1: i32, x2: i32, y2: i32) -> Line {
+        Line::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    fn at(x: i128, y: i128) -> Intersection<i32> {
+        Intersection::Point(Point::whole(Point::new(x, y)))
+    }
+
+    #[test]
+    fn measures_segments() {
+        let segment = line(0, 0, 6, 8);
+
+        assert_eq!(segment.direction(), Point::new(6, 8));
+        assert_eq!(segment.length_squared(), 100);
+        assert_eq!(segment.length(), 10.0);
+        assert_eq!(segment.midpoint(), Point::new(3.0, 4.0));
+        assert_eq!(segment.slope(), Some(8.0 / 6.0));
+        assert_eq!(line(2, 0, 2, 5).slope(), None);
+    }
+
+    #[test]
+    fn finds_the_closest_point() {
+        let segment = line(0, 0, 10, 0);
+
+        assert_eq!(
+            segment.closest_point(Point::new(3, 5)),
+            Point::new(3.0, 0.0)
+        );
+        assert_eq!(
+            segment.closest_point(Point::new(-4, 2)),
+            Point::new(0.0, 0.0)
+        );
+        assert_eq!(
+            segment.closest_point(Point::new(15, -1)),
+            Point::new(10.0, 0.0)
+        );
+        assert_eq!(
+            line(1, 1, 1, 1).closest_point(Point::new(5, 5)),
+            Point::new(1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn intersects_crossing_segments_exactly() {
+        assert_eq!(line(0, 0, 4, 4).intersection(line(0, 4, 4, 0)), at(2, 2));
+        assert_eq!(
+            line(0, 0, 3, 1).intersection(line(0, 1, 3, 0)),
+            Intersection::Point(Point {
+                x: Ratio::new(3, 2),
+                y: Ratio::new(1, 2),
+            })
+        );
+        // Touching at an end point.
+        assert_eq!(line(0, 0, 2, 0).intersection(line(2, 0, 2, 5)), at(2, 0));
+        // The lines cross, but beyond the end of the first segment.
+        assert_eq!(
+            line(0, 0, 1, 1).intersection(line(3, 0, 0, 3)),
+            Intersection::None
+        );
+        assert_eq!(
+            line(0, 0, 4, 0).intersection(line(0, 1, 4, 1)),
+            Intersection::None
+        );
+    }
+
+    #[test]
+    fn handles_colinear_segments() {
+        assert_eq!(
+            line(0, 0, 6, 0).intersection(line(8, 0, 4, 0)),
+            Intersection::Overlap(line(4, 0, 6, 0))
+        );
+        assert_eq!(
+            line(6, 0, 0, 0).intersection(line(4, 0, 8, 0)),
+            Intersection::Overlap(line(6, 0, 4, 0))
+        );
+        assert_eq!(line(0, 0, 2, 2).intersection(line(2, 2, 5, 5)), at(2, 2));
+        assert_eq!(
+            line(0, 0, 1, 0).intersection(line(2, 0, 3, 0)),
+            Intersection::None
+        );
+        assert_eq!(line(2, 0, 2, 0).intersection(line(0, 0, 4, 0)), at(2, 0));
+        assert_eq!(line(1, 1, 1, 1).intersection(line(1, 1, 1, 1)), at(1, 1));
+        assert_eq!(
+            line(1, 1, 1, 1).intersection(line(1, 2, 1, 2)),
+            Intersection::None
+        );
+    }
+
+    #[test]
+    fn does_not_overflow_on_extreme_coordinates() {
+        let (min, max) = (i32::MIN, i32::MAX);
+        let crossing = line(min, min, max, max).intersection(line(min, max, max, min));
+
+        let half = Ratio::new(-1, 2);
+        assert_eq!(crossing, Intersection::Point(Point { x: half, y: half }));
+    }
+
+    #[test]
+    fn measures_segments_spanning_the_whole_range() {
+        let (min, max) = (i32::MIN, i32::MAX);
+        let segment = line(min, 0, max, 0);
+        let width = i128::from(max) - i128::from(min);
+
+        assert_eq!(segment.direction(), Point::new(width, 0));
+        assert_eq!(segment.length_squared(), width * width);
+        assert_eq!(segment.length(), width as f64);
+        assert_eq!(segment.midpoint(), Point::new(-0.5, 0.0));
+        assert_eq!(segment.slope(), Some(0.0));
+        assert_eq!(line(min, min, max, max).slope(), Some(1.0));
+        assert_eq!(
+            segment.closest_point(Point::new(7, max)),
+            Point::new(7.0, 0.0)
+        );
+        assert_eq!(
+            segment.closest_point(Point::new(max, min)),
+            Point::new(f64::from(max), 0.0)
+        );
+    }
+
+    #[test]
+    fn intersects_segments_spanning_the_whole_range() {
+        let (min, max) = (i32::MIN, i32::MAX);
+        let crossing = line(min, 0, max, 1).intersection(line(max, min, min, max));
+        let Intersection::Point(point) = crossing else {
+            panic!("expected a crossing, got {:?}", crossing);
+        };
+
+        // Exactly on both lines: the cross products with their directions are zero.
+        for segment in [line(min, 0, max, 1), line(max, min, min, max)] {
+            let start = Point::whole(segment.start.widen());
+            let d = segment.direction();
+            let (x, y) = (point.x, point.y);
+            assert_eq!(
+                (x.numerator() - start.x.numerator() * x.denominator()) * d.y * y.denominator(),
+                (y.numerator() - start.y.numerator() * y.denominator()) * d.x * x.denominator()
+            );
+        }
+    }
+
+    #[test]
+    fn float_segments_share_the_implementation() {
+        let a = Line::new(Point::new(0.0, 0.0), Point::new(1.0, 1.0));
+        let b = Line::new(Point::new(0.0, 1.0), Point::new(1.0, 0.0));
+
+        match a.intersection(b) {
+            Intersection::Point(point) => assert_eq!(point.to_f64(), Point::new(0.5, 0.5)),
+            other => panic!("expected a crossing, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn clips_to_a_rectangle() {
+        let rect = Rect::new(Point::new(10, 10), Point::new(0, 0));
+        let clip = |segment: Line| segment.clip(rect).map(|clipped| clipped.to_whole());
+
+        assert_eq!(clip(line(1, 1, 9, 9)), Some(Some(line(1, 1, 9, 9))));
+        assert_eq!(clip(line(-5, 5, 15, 5)), Some(Some(line(0, 5, 10, 5))));
+        assert_eq!(clip(line(5, 20, 5, -20)), Some(Some(line(5, 10, 5, 0))));
+        assert_eq!(clip(line(11, 0, 12, 12)), None);
+        // Both ends outside on different sides, passing above the corner.
+        assert_eq!(clip(line(-1, 9, 1, 12)), None);
+
+        let clipped = line(-1, 0, 2, 10).clip(rect).unwrap();
+        assert_eq!(clipped.start.x, Ratio::whole(0));
+        assert_eq!(clipped.start.y, Ratio::new(10, 3));
+        assert_eq!(clipped.end, Point::whole(Point::new(2, 10)));
+        assert_eq!(clipped.to_whole::<i32>(), None);
+    }
+
+    #[test]
+    fn clips_float_segments() {
+        let rect = Rect::new(Point::new(0.0, 0.0), Point::new(1.0, 1.0));
+        let segment = Line::new(Point::new(-1.0, 0.5), Point::new(3.0, 0.5));
+
+        assert_eq!(
+            segment.clip(rect).map(Line::to_f64),
+            Some(Line::new(Point::new(0.0, 0.5), Point::new(1.0, 0.5)))
+        );
+        for bad in [f64::NAN, f64::INFINITY] {
+            let segment = Line::new(Point::new(bad, 0.5), Point::new(3.0, 0.5));
+            assert_eq!(segment.clip(rect), None);
+        }
+    }
+}
@@ -1,53 +1,11 @@
-// Use `Associated`` traits (traits that use the `type` keyword) when there should be only a single
-// implementation of a trait per type. However, if there are many possible implementations of the
-// trait per type, then use `Generic` traits
-
-trait Addition<T, U> {
-    fn add(&self, rhs: T) -> U;
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-#[derive(Debug)]
-struct Line {
-    start: Point,
-    end: Point,
-}
-
-impl Addition<Point, Point> for Point {
-    fn add(&self, rhs: Point) -> Point {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl Addition<i32, Point> for Point {
-    fn add(&self, rhs: i32) -> Point {
-        Point {
-            x: self.x + rhs,
-            y: self.y + rhs,
-        }
-    }
-}
-
-impl Addition<Point, Line> for Point {
-    fn add(&self, rhs: Point) -> Line {
-        Line {
-            start: self.to_owned(),
-            end: rhs,
-        }
-    }
-}
+// Demo of the generic traits in the `generic_traits` library crate (src/lib.rs).
+use generic_traits::{
+    closest_pair, convex_hull, Addition, Intersection, Line, Point, Polyline, Rect,
+};
 
 fn main() {
     // Adding two points
-    let p1 = Point { x: 1, y: 2 };
+    let p1: Point = Point { x: 1, y: 2 };
     let p2 = Point { x: 2, y: 5 };
 
     let p3: Point = p1.add(p2);
@@ -55,16 +13,103 @@ fn main() {
     println!("p1 + p2 = {:?}", p3);
 
     // Adding integer to a point
-    let p1 = Point { x: 2, y: 3 };
-    let p2 = p1.add(3);
+    let p1: Point = Point { x: 2, y: 3 };
+    let p2: Point = p1.add(3);
 
     println!("Adding 3 to p1 = {:?}", p2);
 
     // Creating a Line by adding two Points
-    let p1 = Point { x: 2, y: 1 };
+    let p1: Point = Point { x: 2, y: 1 };
     let p2 = Point { x: 3, y: 5 };
 
     let line: Line = p1.add(p2);
 
     println!("Start: {:?}   End: {:?}", line.start, line.end);
+
+    // The same points as vectors, through the std operator traits
+    let a = Point::new(3, 4);
+    let b = Point::new(-4, 3);
+    println!("a + b = {:?}   a - b = {:?}   -a = {:?}", a + b, a - b, -a);
+    println!(
+        "2a = {:?}   a . b = {}   a x b = {}",
+        a * 2,
+        a.dot(b),
+        a.cross(b)
+    );
+    println!(
+        "|a| = {}   unit a = {:?}   distance a to b = {:.3}",
+        a.magnitude(),
+        a.normalize(),
+        a.distance(b)
+    );
+
+    // Float points use the same implementation
+    let mut c = Point::new(0.5, 1.5);
+    c *= 2.0;
+    c += Point::new(1.0, 1.0);
+    println!("c = {:?}   |c| = {:.3}", c, c.magnitude());
+
+    // Segment geometry on the line built above
+    println!(
+        "line: length {:.3}, midpoint {:?}, slope {:?}, closest to (0, 0) {:?}",
+        line.length(),
+        line.midpoint(),
+        line.slope(),
+        line.closest_point(Point::new(0, 0))
+    );
+    let other = Line::new(Point::new(0, 4), Point::new(4, 2));
+    match line.intersection(other) {
+        Intersection::Point(point) => println!("crossing at {:?}", point.to_f64()),
+        Intersection::Overlap(overlap) => println!("overlapping along {:?}", overlap),
+        Intersection::None => println!("no intersection"),
+    }
+    let window = Rect::new(Point::new(0, 0), Point::new(3, 3));
+    match line.clip(window) {
+        Some(clipped) => println!("clipped to {:?}", clipped.to_f64()),
+        None => println!("line is outside the window"),
+    }
+
+    // Keep adding points: a Line plus a Point is a Polyline, which can be closed
+    let path: Polyline = line.add(Point::new(6, 5)).add(Point::new(6, 1));
+    println!(
+        "path {:?}: length {:.3}, self-intersecting {}",
+        path.points(),
+        path.length(),
+        path.is_self_intersecting()
+    );
+    let polygon = path.close();
+    println!(
+        "polygon: perimeter {:.3}, winding {:?}, self-intersecting {}",
+        polygon.perimeter(),
+        polygon.winding(),
+        polygon.is_self_intersecting()
+    );
+
+    // Drawing the line into a small character grid
+    let mut grid = vec![vec!['.'; 8]; 7];
+    for pixel in line.pixels() {
+        grid[pixel.y as usize][pixel.x as usize] = '#';
+    }
+    for row in grid.iter().rev() {
+        println!("{}", row.iter().collect::<String>());
+    }
+    let shades: Vec<String> = line
+        .pixels_antialiased()
+        .map(|(pixel, coverage)| format!("({}, {}) {:.2}", pixel.x, pixel.y, coverage))
+        .collect();
+    println!("anti-aliased: {}", shades.join(", "));
+
+    // Algorithms over sets of points
+    let cloud: Vec<Point> = [(0, 0), (5, 1), (2, 2), (6, 6), (1, 5), (3, 4), (2, 3)]
+        .iter()
+        .map(|&(x, y)| Point::new(x, y))
+        .collect();
+    println!("convex hull: {:?}", convex_hull(&cloud));
+    println!("closest pair: {:?}", closest_pair(&cloud));
+    println!(
+        "polygon: area {}, centroid {:?}, (4, 3) is {:?}",
+        polygon.area(),
+        polygon.centroid(),
+        polygon.locate(Point::new(4, 3))
+    );
 }
@@ -0,0 +1,238 @@
+// The numeric types a `Point` can be made of. Integer and floating-point points share one
+// implementation of the geometry written against this trait.
+//
+// Exact geometry (segment intersections, clipping) multiplies coordinates together, which
+// would overflow an `i32` long before the coordinates themselves do. Those computations
+// run in the `Wide` type instead, and their results are `Ratio`s of it: an intersection of
+// two integer segments usually isn't on an integer point, but it is always a fraction.
+//
+// The geometry itself (lines, paths, rasterization and the algorithms) is only offered for
+// `Coord` types, whose products all fit in their `Wide` type.
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+pub trait Num:
+    Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+{
+    // i128 for the integers, f64 for the floats.
+    type Wide: Num;
+
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn to_f64(self) -> f64;
+
+    fn widen(self) -> Self::Wide;
+
+    // Back from `Wide`, `None` if the value doesn't fit.
+    fn narrow(wide: Self::Wide) -> Option<Self>;
+
+    // The factor `Ratio::new` divides numerator and denominator by: the positive greatest
+    // common divisor for integers, and `other` itself for floats, so that float ratios
+    // always end up over 1.
+    fn gcd(self, other: Self) -> Self;
+
+    // `None` where negating overflows, i.e. for the MIN of an integer type.
+    fn checked_neg(self) -> Option<Self>;
+}
+
+macro_rules! impl_num_int {
+    ($($t:ty),*) => {
+        $(
+            impl Num for $t {
+                type Wide = i128;
+
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn widen(self) -> i128 {
+                    self as i128
+                }
+
+                fn narrow(wide: i128) -> Option<Self> {
+                    wide.try_into().ok()
+                }
+
+                // `unsigned_abs` because `abs` overflows on MIN. The only divisor that
+                // doesn't fit back is 2^(BITS-1), of MIN and 0 or MIN and MIN, and MIN
+                // itself divides those just as well: `Ratio::new` fixes the sign.
+                fn gcd(self, other: Self) -> Self {
+                    let (mut a, mut b) = (self.unsigned_abs(), other.unsigned_abs());
+                    while b != 0 {
+                        (a, b) = (b, a % b);
+                    }
+                    a.try_into().unwrap_or(Self::MIN)
+                }
+
+                fn checked_neg(self) -> Option<Self> {
+                    // The inherent method, not this one.
+                    <$t>::checked_neg(self)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_num_float {
+    ($($t:ty),*) => {
+        $(
+            impl Num for $t {
+                type Wide = f64;
+
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn widen(self) -> f64 {
+                    self as f64
+                }
+
+                fn narrow(wide: f64) -> Option<Self> {
+                    Some(wide as $t)
+                }
+
+                fn gcd(self, other: Self) -> Self {
+                    other
+                }
+
+                fn checked_neg(self) -> Option<Self> {
+                    Some(-self)
+                }
+            }
+        )*
+    };
+}
+
+impl_num_int!(i32, i64, i128);
+impl_num_float!(f32, f64);
+
+// The coordinate types geometry is computed for. Products of up to three i32 coordinates
+// always fit in an i128, so i32 geometry is exact and never overflows; floats round but
+// don't overflow into a panic. i64 and i128 are `Num`s, i128 to be the `Wide` type of
+// the other integers, but not `Coord`s, since their products can overflow even an i128.
+// The trait is sealed so that no other types can be added.
+pub trait Coord: Num + sealed::Sealed {}
+
+impl Coord for i32 {}
+impl Coord for f32 {}
+impl Coord for f64 {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for i32 {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+// A fraction in lowest terms with a positive denominator, so equal values compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ratio<T> {
+    num: T,
+    den: T,
+}
+
+impl<T: Num> Ratio<T> {
+    // Panics if `den` is zero, or if the ratio can't be written with a positive
+    // denominator, like 1 / i32::MIN whose denominator would be 2^31. `checked_new`
+    // returns `None` instead.
+    pub fn new(num: T, den: T) -> Ratio<T> {
+        assert!(den != T::ZERO, "ratio with a zero denominator");
+        Ratio::checked_new(num, den).expect("ratio out of range")
+    }
+
+    pub fn checked_new(num: T, den: T) -> Option<Ratio<T>> {
+        if den == T::ZERO {
+            return None;
+        }
+        let factor = num.gcd(den);
+        let (num, den) = (num / factor, den / factor);
+        if den < T::ZERO {
+            Some(Ratio {
+                num: num.checked_neg()?,
+                den: den.checked_neg()?,
+            })
+        } else {
+            Some(Ratio { num, den })
+        }
+    }
+
+    pub fn whole(value: T) -> Ratio<T> {
+        Ratio {
+            num: value,
+            den: T::ONE,
+        }
+    }
+
+    pub fn numerator(self) -> T {
+        self.num
+    }
+
+    pub fn denominator(self) -> T {
+        self.den
+    }
+
+    // The value if it is a whole number.
+    pub fn to_whole(self) -> Option<T> {
+        (self.den == T::ONE).then_some(self.num)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.num.to_f64() / self.den.to_f64()
+    }
+
+    // Compares with a whole number without dividing. Panics if either is NaN.
+    pub fn cmp_whole(self, value: T) -> Ordering {
+        self.num
+            .partial_cmp(&(value * self.den))
+            .expect("ratios are not NaN")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratios_are_kept_in_lowest_terms() {
+        assert_eq!(Ratio::new(6, -4), Ratio::new(-3, 2));
+        assert_eq!(Ratio::new(-6, -4).numerator(), 3);
+        assert_eq!(Ratio::new(0, -5), Ratio::whole(0));
+        assert_eq!(Ratio::new(12, 4).to_whole(), Some(3));
+        assert_eq!(Ratio::new(7, 2).to_whole(), None);
+        assert_eq!(Ratio::new(7, 2).to_f64(), 3.5);
+        assert_eq!(Ratio::new(7, 2).cmp_whole(3), Ordering::Greater);
+        assert_eq!(Ratio::new(-7, 2).cmp_whole(-3), Ordering::Less);
+        assert_eq!(Ratio::new(3.0, -2.0), Ratio::whole(-1.5));
+        assert_eq!(5i32.gcd(-15), 5);
+        assert_eq!(i32::MIN.gcd(6), 2);
+        assert_eq!(Ratio::new(i32::MIN, i32::MIN), Ratio::whole(1));
+        assert_eq!(Ratio::new(0, i64::MIN), Ratio::whole(0));
+        assert_eq!(Ratio::checked_new(1, i32::MIN), None);
+        assert_eq!(Ratio::checked_new(i32::MIN, -1), None);
+        assert_eq!(
+            Ratio::checked_new(2, i32::MIN),
+            Some(Ratio::new(-1, 1 << 30))
+        );
+        assert_eq!(Ratio::checked_new(1, 0), None);
+    }
+}
@@ -0,0 +1,245 @@
+// Paths built by chaining `Addition`: two points make a `Line`, and adding more points to
+// it makes a `Polyline`. Closing a polyline gives a `Polygon`.
+//
+//     let line: Line = p1.add(p2);
+//     let path = line.add(p3).add(p4);
+//     let triangle = line.add(p3).close();
+use crate::algorithms::{self, Location};
+use crate::line::{Intersection, Line};
+use crate::num::{Coord, Num};
+use crate::point::Point;
+use crate::Addition;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polyline<T = i32> {
+    points: Vec<Point<T>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polygon<T = i32> {
+    vertices: Vec<Point<T>>,
+}
+
+// Which way a polygon goes round, with the y axis pointing up. (On a screen, where y
+// points down, the two swap.)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Winding {
+    CounterClockwise,
+    Clockwise,
+}
+
+// Whether any two segments meet other than where consecutive segments join. Segments are
+// consecutive if they follow each other, and in a closed path the last one also joins the
+// first one.
+fn crosses_itself<T: Coord>(segments: &[Line<T>], closed: bool) -> bool {
+    let n = segments.len();
+    for i in 0..n {
+        for j in i + 1..n {
+            let joined = j == i + 1 || (closed && i == 0 && j == n - 1);
+            match segments[i].intersection(segments[j]) {
+                Intersection::None => {}
+                // Consecutive segments always share their joint; more than that means the
+                // path doubles back on itself.
+                Intersection::Point(_) if joined => {}
+                _ => return true,
+            }
+        }
+    }
+    false
+}
+
+// The segments between consecutive points, skipping repeated points.
+fn segments<T: Coord>(points: &[Point<T>]) -> Vec<Line<T>> {
+    points
+        .windows(2)
+        .map(|pair| Line::new(pair[0], pair[1]))
+        .filter(|segment| segment.start != segment.end)
+        .collect()
+}
+
+impl<T: Coord> Polyline<T> {
+    pub fn new(points: Vec<Point<T>>) -> Polyline<T> {
+        Polyline { points }
+    }
+
+    pub fn points(&self) -> &[Point<T>] {
+        &self.points
+    }
+
+    pub fn push(&mut self, point: Point<T>) {
+        self.points.push(point);
+    }
+
+    pub fn segments(&self) -> Vec<Line<T>> {
+        segments(&self.points)
+    }
+
+    pub fn length(&self) -> f64 {
+        self.segments().iter().map(|segment| segment.length()).sum()
+    }
+
+    // Whether the path touches or crosses itself, including by returning to where it
+    // started: use `close` for loops.
+    pub fn is_self_intersecting(&self) -> bool {
+        crosses_itself(&self.segments(), false)
+    }
+
+    // The polygon with the polyline's points as vertices.
+    pub fn close(self) -> Polygon<T> {
+        Polygon::new(self.points)
+    }
+}
+
+impl<T: Coord> From<Line<T>> for Polyline<T> {
+    fn from(line: Line<T>) -> Polyline<T> {
+        Polyline::new(vec![line.start, line.end])
+    }
+}
+
+impl<T: Coord> Polygon<T> {
+    // The last vertex is joined back to the first, so it shouldn't repeat the first one.
+    pub fn new(vertices: Vec<Point<T>>) -> Polygon<T> {
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point<T>] {
+        &self.vertices
+    }
+
+    // The sides, including the closing one from the last vertex back to the first.
+    pub fn edges(&self) -> Vec<Line<T>> {
+        let mut points = self.vertices.clone();
+        if let Some(&first) = self.vertices.first() {
+            points.push(first);
+        }
+        segments(&points)
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.edges().iter().map(|edge| edge.length()).sum()
+    }
+
+    // Twice the area, positive for counter-clockwise polygons (the shoelace formula). Exact
+    // for integer vertices.
+    pub fn signed_area_doubled(&self) -> T::Wide {
+        algorithms::signed_area_doubled(&self.vertices)
+    }
+
+    pub fn area(&self) -> f64 {
+        algorithms::polygon_area(&self.vertices)
+    }
+
+    // `None` for polygons without area.
+    pub fn centroid(&self) -> Option<Point<f64>> {
+        algorithms::polygon_centroid(&self.vertices)
+    }
+
+    pub fn locate(&self, point: Point<T>) -> Location {
+        algorithms::point_in_polygon(point, &self.vertices)
+    }
+
+    // `None` for polygons without area, e.g. all vertices on one line.
+    pub fn winding(&self) -> Option<Winding> {
+        let area = self.signed_area_doubled();
+        if area > T::Wide::ZERO {
+            Some(Winding::CounterClockwise)
+        } else if area < T::Wide::ZERO {
+            Some(Winding::Clockwise)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_self_intersecting(&self) -> bool {
+        crosses_itself(&self.edges(), true)
+    }
+}
+
+impl<T: Coord> Addition<Point<T>, Polyline<T>> for Line<T> {
+    fn add(&self, rhs: Point<T>) -> Polyline<T> {
+        Polyline::new(vec![self.start, self.end, rhs])
+    }
+}
+
+impl<T: Coord> Addition<Point<T>, Polyline<T>> for Polyline<T> {
+    fn add(&self, rhs: Point<T>) -> Polyline<T> {
+        let mut polyline = self.clone();
+        polyline.push(rhs);
+        polyline
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i32, y: i32) -> Point {
+        Point::new(x, y)
+    }
+
+    #[test]
+    fn builds_paths_by_adding_points() {
+        let line: Line = p(0, 0).add(p(3, 4));
+        let path = line.add(p(3, 0)).add(p(0, 0));
+
+        assert_eq!(path.points(), &[p(0, 0), p(3, 4), p(3, 0), p(0, 0)]);
+        assert_eq!(path.length(), 12.0);
+        assert_eq!(path.segments().len(), 3);
+        assert_eq!(Polyline::from(line).points().len(), 2);
+    }
+
+    #[test]
+    fn polygons_know_their_winding() {
+        let bottom: Line = p(0, 0).add(p(4, 0));
+        let square = bottom.add(p(4, 4)).add(p(0, 4)).close();
+
+        assert_eq!(square.edges().len(), 4);
+        assert_eq!(square.perimeter(), 16.0);
+        assert_eq!(square.signed_area_doubled(), 32);
+        assert_eq!(square.winding(), Some(Winding::CounterClockwise));
+
+        let mut reversed = square.vertices().to_vec();
+        reversed.reverse();
+        assert_eq!(Polygon::new(reversed).winding(), Some(Winding::Clockwise));
+        assert_eq!(
+            Polygon::new(vec![p(0, 0), p(1, 1), p(2, 2)]).winding(),
+            None
+        );
+        assert_eq!(Polygon::<i32>::new(Vec::new()).winding(), None);
+    }
+
+    #[test]
+    fn detects_self_intersections() {
+        let zigzag = Polyline::new(vec![p(0, 0), p(2, 2), p(4, 0), p(6, 2)]);
+        assert!(!zigzag.is_self_intersecting());
+
+        let crossing = Polyline::new(vec![p(0, 0), p(4, 4), p(4, 0), p(0, 4)]);
+        assert!(crossing.is_self_intersecting());
+
+        // Going back along the previous segment.
+        let doubling_back = Polyline::new(vec![p(0, 0), p(4, 0), p(2, 0)]);
+        assert!(doubling_back.is_self_intersecting());
+
+        // Repeated points are not a self-intersection.
+        let repeated = Polyline::new(vec![p(0, 0), p(1, 0), p(1, 0), p(2, 1)]);
+        assert!(!repeated.is_self_intersecting());
+
+        let square = Polygon::new(vec![p(0, 0), p(4, 0), p(4, 4), p(0, 4)]);
+        assert!(!square.is_self_intersecting());
+        let bowtie = Polygon::new(vec![p(0, 0), p(4, 4), p(4, 0), p(0, 4)]);
+        assert!(bowtie.is_self_intersecting());
+        // Two vertices at the same place.
+        let pinched = Polygon::new(vec![p(0, 0), p(4, 0), p(2, 2), p(4, 4), p(0, 4), p(2, 2)]);
+        assert!(pinched.is_self_intersecting());
+    }
+
+    #[test]
+    fn float_paths_share_the_implementation() {
+        let base: Line<f64> = Point::new(0.0, 0.0).add(Point::new(1.0, 0.0));
+        let triangle = base.add(Point::new(0.0, 1.0)).close();
+
+        assert_eq!(triangle.signed_area_doubled(), 1.0);
+        assert_eq!(triangle.perimeter(), 2.0 + 2f64.sqrt());
+        assert!(!triangle.is_self_intersecting());
+    }
+}
@@ -0,0 +1,210 @@
+// 2D vector algebra on `Point`. Points double as vectors: the operator traits work
+// component-wise and multiplying by a number scales both coordinates.
+//
+// The method-style `Addition` trait from lib.rs is still implemented too; the std `Add`
+// trait isn't imported anywhere here so `p.add(q)` keeps meaning `Addition::add`.
+use std::ops;
+
+use crate::num::{Num, Ratio};
+use crate::Addition;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Num> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    pub fn dot(self, other: Point<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // The z component of the 3D cross product: positive when `other` is counter-clockwise
+    // from `self`, zero when they are parallel.
+    pub fn cross(self, other: Point<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    // Exact for integer points, unlike `magnitude`.
+    pub fn magnitude_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn magnitude(self) -> f64 {
+        self.to_f64().magnitude_squared().sqrt()
+    }
+
+    // The unit vector pointing the same way, `None` for the zero vector.
+    pub fn normalize(self) -> Option<Point<f64>> {
+        let magnitude = self.magnitude();
+        if magnitude == 0.0 {
+            return None;
+        }
+        let point = self.to_f64();
+        Some(Point::new(point.x / magnitude, point.y / magnitude))
+    }
+
+    pub fn distance_squared(self, other: Point<T>) -> T {
+        (other - self).magnitude_squared()
+    }
+
+    pub fn distance(self, other: Point<T>) -> f64 {
+        (other.to_f64() - self.to_f64()).magnitude()
+    }
+
+    pub fn to_f64(self) -> Point<f64> {
+        Point::new(self.x.to_f64(), self.y.to_f64())
+    }
+
+    pub fn widen(self) -> Point<T::Wide> {
+        Point::new(self.x.widen(), self.y.widen())
+    }
+}
+
+// Exact points with fractional coordinates, as found by `Line::intersection` and
+// `Line::clip`.
+impl<T: Num> Point<Ratio<T>> {
+    pub fn whole(point: Point<T>) -> Point<Ratio<T>> {
+        Point {
+            x: Ratio::whole(point.x),
+            y: Ratio::whole(point.y),
+        }
+    }
+
+    // The point if both coordinates are whole numbers that fit in `N`, usually the type
+    // of the points the exact point was computed from.
+    pub fn to_whole<N: Num<Wide = T>>(self) -> Option<Point<N>> {
+        Some(Point::new(
+            N::narrow(self.x.to_whole()?)?,
+            N::narrow(self.y.to_whole()?)?,
+        ))
+    }
+
+    pub fn to_f64(self) -> Point<f64> {
+        Point::new(self.x.to_f64(), self.y.to_f64())
+    }
+}
+
+impl<T: Num> ops::Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Num> ops::Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Num> ops::Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+// Scaling by a number of the same type.
+impl<T: Num> ops::Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, rhs: T) -> Point<T> {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Num> ops::AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Point<T>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: Num> ops::SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Point<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Num> ops::MulAssign<T> for Point<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        self.x *= rhs;
+        self.y *= rhs;
+    }
+}
+
+impl<T: Num> Addition<Point<T>, Point<T>> for Point<T> {
+    fn add(&self, rhs: Point<T>) -> Point<T> {
+        *self + rhs
+    }
+}
+
+impl<T: Num> Addition<T, Point<T>> for Point<T> {
+    fn add(&self, rhs: T) -> Point<T> {
+        Point::new(self.x + rhs, self.y + rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators_work_component_wise() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -3);
+
+        assert_eq!(a + b, Point::new(5, -1));
+        assert_eq!(a - b, Point::new(-3, 5));
+        assert_eq!(-a, Point::new(-1, -2));
+        assert_eq!(b * 2, Point::new(8, -6));
+
+        let mut c = a;
+        c += b;
+        c -= Point::new(1, 1);
+        c *= 3;
+        assert_eq!(c, Point::new(12, -6));
+        assert_eq!(Addition::<i32, Point>::add(&a, 1), Point::new(2, 3));
+    }
+
+    #[test]
+    fn products_and_lengths() {
+        let a = Point::new(3, 4);
+        let b = Point::new(-4, 3);
+
+        assert_eq!(a.dot(b), 0);
+        assert_eq!(a.cross(b), 25);
+        assert_eq!(b.cross(a), -25);
+        assert_eq!(a.magnitude_squared(), 25);
+        assert_eq!(a.magnitude(), 5.0);
+        assert_eq!(a.distance(b), 50f64.sqrt());
+        assert_eq!(a.distance_squared(b), 50);
+        assert_eq!(a.normalize(), Some(Point::new(0.6, 0.8)));
+        assert_eq!(Point::new(0, 0).normalize(), None);
+    }
+
+    #[test]
+    fn float_points_share_the_implementation() {
+        let a = Point::new(1.5, -2.0);
+        let b = Point::new(0.5, 0.5);
+
+        assert_eq!(a + b, Point::new(2.0, -1.5));
+        assert_eq!(a * 2.0, Point::new(3.0, -4.0));
+        assert_eq!(a.dot(b), -0.25);
+        assert_eq!(a.cross(b), 1.75);
+        assert_eq!(
+            Point::new(0.0, -2.0).normalize(),
+            Some(Point::new(0.0, -1.0))
+        );
+    }
+}
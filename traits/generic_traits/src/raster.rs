@@ -0,0 +1,533 @@
+// Rasterizing segments onto a grid of integer pixels, e.g. to draw into a character
+// canvas or mark the cells of a game map:
+//
+// - `pixels` is Bresenham's algorithm: integer-only, one pixel per step along the longer
+//   axis, each the pixel nearest to the true line.
+// - `pixels_dda` steps along the line in floating point and rounds. It works for float
+//   end points too and gives the same pixels as Bresenham except where the line passes
+//   exactly halfway between two pixels.
+// - `pixels_antialiased` is Xiaolin Wu's algorithm: two pixels per step, each with the
+//   fraction (0 to 1] of it the line covers, for blending.
+//
+// Pixels have i32 coordinates, so the float-based `pixels_dda` and `pixels_antialiased`
+// only draw the part of a line inside the range of an i32.
+use std::iter::FusedIterator;
+
+use crate::line::Line;
+use crate::num::Coord;
+use crate::point::Point;
+
+pub struct Pixels {
+    x: i64,
+    y: i64,
+    dx: i64,
+    // Negative, as in the usual formulation of the algorithm.
+    dy: i64,
+    step_x: i64,
+    step_y: i64,
+    error: i64,
+    remaining: u64,
+}
+
+impl Iterator for Pixels {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Point<i32>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        // The coordinates stay between the end points, so they fit in an i32.
+        let pixel = Point::new(self.x as i32, self.y as i32);
+        let doubled = 2 * self.error;
+        if doubled >= self.dy {
+            self.error += self.dy;
+            self.x += self.step_x;
+        }
+        if doubled <= self.dx {
+            self.error += self.dx;
+            self.y += self.step_y;
+        }
+        Some(pixel)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Lines can have more pixels than a usize counts on 32-bit targets.
+        let remaining = usize::try_from(self.remaining).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Pixels {}
+
+impl FusedIterator for Pixels {}
+
+pub struct DdaPixels {
+    start: Point<f64>,
+    delta: Point<f64>,
+    steps: u64,
+    next_step: u64,
+}
+
+impl Iterator for DdaPixels {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Point<i32>> {
+        if self.next_step > self.steps {
+            return None;
+        }
+        // Computed from the start each time rather than by adding up increments, so
+        // rounding errors don't accumulate along long lines.
+        let t = if self.steps == 0 {
+            0.0
+        } else {
+            self.next_step as f64 / self.steps as f64
+        };
+        self.next_step += 1;
+        let point = self.start + self.delta * t;
+        // The line was clipped to the i32 range, so rounding stays inside it.
+        Some(Point::new(point.x.round() as i32, point.y.round() as i32))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.steps + 1 - self.next_step).unwrap_or(usize::MAX);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for DdaPixels {}
+
+impl FusedIterator for DdaPixels {}
+
+// Pixels with their coverage. Wu's algorithm works on lines running left to right with
+// a gradient of at most 1, so other lines are mirrored into that shape: steep lines swap
+// x and y, and lines going left are walked from their last column back to the first.
+pub struct AntialiasedPixels {
+    steep: bool,
+    reversed: bool,
+    first_x: f64,
+    first_y: f64,
+    gradient: f64,
+    // The end columns are only partly covered, by how far the line reaches into them.
+    first_coverage: f64,
+    last_y: f64,
+    last_coverage: f64,
+    // Index of the last column, counting from `first_x`.
+    last: u64,
+    remaining: u64,
+    // The second pixel of the column last visited.
+    pending: Option<(Point<i32>, f64)>,
+}
+
+impl AntialiasedPixels {
+    // Height and coverage of the line in the column `index` columns right of `first_x`.
+    fn column(&self, index: u64) -> (f64, f64) {
+        if index == 0 {
+            (self.first_y, self.first_coverage)
+        } else if index == self.last {
+            (self.last_y, self.last_coverage)
+        } else {
+            (self.first_y + self.gradient * index as f64, 1.0)
+        }
+    }
+
+    // The pixel at (x, y) in the mirrored coordinates, if the line covers any of it. The
+    // line was clipped to the i32 range, but the second pixel of a column can be one past
+    // it, and is left out then.
+    fn pixel(&self, x: f64, y: f64, coverage: f64) -> Option<(Point<i32>, f64)> {
+        let (x, y) = if self.steep { (y, x) } else { (x, y) };
+        let fits = |value: f64| (f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(&value);
+        (coverage > 0.0 && fits(x) && fits(y)).then(|| (Point::new(x as i32, y as i32), coverage))
+    }
+}
+
+impl Iterator for AntialiasedPixels {
+    type Item = (Point<i32>, f64);
+
+    fn next(&mut self) -> Option<(Point<i32>, f64)> {
+        loop {
+            if let Some(pixel) = self.pending.take() {
+                return Some(pixel);
+            }
+            if self.remaining == 0 {
+                return None;
+            }
+            self.remaining -= 1;
+            let index = if self.reversed {
+                self.remaining
+            } else {
+                self.last - self.remaining
+            };
+            // Both pixels of the column, split by where the line passes between them.
+            let (y, coverage) = self.column(index);
+            let x = self.first_x + index as f64;
+            let lower = self.pixel(x, y.floor(), (1.0 - y.fract_positive()) * coverage);
+            let upper = self.pixel(x, y.floor() + 1.0, y.fract_positive() * coverage);
+            let (first, second) = if self.reversed {
+                (upper, lower)
+            } else {
+                (lower, upper)
+            };
+            self.pending = second;
+            if first.is_some() {
+                return first;
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = usize::from(self.pending.is_some());
+        let upper = usize::try_from(self.remaining)
+            .ok()
+            .and_then(|columns| columns.checked_mul(2))
+            .and_then(|pixels| pixels.checked_add(pending));
+        (pending, upper)
+    }
+}
+
+impl FusedIterator for AntialiasedPixels {}
+
+impl Line<i32> {
+    // Bresenham's line from `start` to `end`, both included.
+    pub fn pixels(self) -> Pixels {
+        let (x0, y0) = (self.start.x as i64, self.start.y as i64);
+        let (x1, y1) = (self.end.x as i64, self.end.y as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        Pixels {
+            x: x0,
+            y: y0,
+            dx,
+            dy,
+            step_x: if x0 < x1 { 1 } else { -1 },
+            step_y: if y0 < y1 { 1 } else { -1 },
+            error: dx + dy,
+            remaining: dx.max(-dy) as u64 + 1,
+        }
+    }
+}
+
+impl<T: Coord> Line<T> {
+    // The pixels nearest to evenly spaced points on the line, from `start` to `end`. A
+    // line with an infinite or NaN coordinate has no pixels.
+    pub fn pixels_dda(self) -> DdaPixels {
+        let Some((start, end)) = clip_to_i32(self.start.to_f64(), self.end.to_f64()) else {
+            return DdaPixels {
+                start: Point::new(0.0, 0.0),
+                delta: Point::new(0.0, 0.0),
+                steps: 0,
+                next_step: 1,
+            };
+        };
+        let delta = end - start;
+        DdaPixels {
+            start,
+            delta,
+            steps: delta.x.abs().max(delta.y.abs()).ceil() as u64,
+            next_step: 0,
+        }
+    }
+
+    // Xiaolin Wu's anti-aliased line. Pixels the line doesn't cover at all are left out,
+    // and a line shorter than a pixel only draws the column of its start. The columns
+    // are worked out one at a time, so even the longest lines don't allocate. Lines that
+    // `pixels_dda` rejects have no pixels here either.
+    pub fn pixels_antialiased(self) -> AntialiasedPixels {
+        let clipped = clip_to_i32(self.start.to_f64(), self.end.to_f64());
+        let finite = clipped.is_some();
+        let (mut a, mut b) = clipped.unwrap_or((Point::new(0.0, 0.0), Point::new(0.0, 0.0)));
+        let steep = (b.y - a.y).abs() > (b.x - a.x).abs();
+        if steep {
+            a = Point::new(a.y, a.x);
+            b = Point::new(b.y, b.x);
+        }
+        let reversed = a.x > b.x;
+        if reversed {
+            (a, b) = (b, a);
+        }
+        let dx = b.x - a.x;
+        let gradient = if dx == 0.0 { 1.0 } else { (b.y - a.y) / dx };
+
+        let first_x = (a.x + 0.5).floor();
+        let last_x = (b.x + 0.5).floor();
+        let last = (last_x - first_x) as u64;
+        AntialiasedPixels {
+            steep,
+            reversed,
+            first_x,
+            first_y: a.y + gradient * (first_x - a.x),
+            gradient,
+            first_coverage: 1.0 - (a.x + 0.5).fract_positive(),
+            last_y: b.y + gradient * (last_x - b.x),
+            last_coverage: (b.x + 0.5).fract_positive(),
+            last,
+            remaining: if finite { last + 1 } else { 0 },
+            pending: None,
+        }
+    }
+}
+
+// The part of the line from `start` to `end` whose coordinates are in the range of an
+// i32, `None` if there is none or a coordinate is infinite or NaN. Lines already inside
+// the range come back unchanged.
+fn clip_to_i32(start: Point<f64>, end: Point<f64>) -> Option<(Point<f64>, Point<f64>)> {
+    let ends = [start.x, start.y, end.x, end.y];
+    if !ends.iter().all(|value| value.is_finite()) {
+        return None;
+    }
+    let (min, max) = (f64::from(i32::MIN), f64::from(i32::MAX));
+    // Points on the line are start + (end - start) * t, with the direction halved so that
+    // it can't overflow to infinity. The visible part is a range of t, and each clipped
+    // end remembers the axis and border it was clipped at.
+    let half = (end * 0.5) - (start * 0.5);
+    let mut enter = (0.0, None);
+    let mut leave = (1.0, None);
+    for (axis, from, d) in [(0, start.x, half.x), (1, start.y, half.y)] {
+        if d == 0.0 {
+            if from < min || from > max {
+                return None;
+            }
+            continue;
+        }
+        let at_min = (min * 0.5 - from * 0.5) / d;
+        let at_max = (max * 0.5 - from * 0.5) / d;
+        let (first, last) = if at_min <= at_max {
+            ((at_min, min), (at_max, max))
+        } else {
+            ((at_max, max), (at_min, min))
+        };
+        if first.0 > enter.0 {
+            enter = (first.0, Some((axis, first.1)));
+        }
+        if last.0 < leave.0 {
+            leave = (last.0, Some((axis, last.1)));
+        }
+    }
+    if enter.0 > leave.0 {
+        return None;
+    }
+    // Exact on the axis that was clipped, so the far end of a long line doesn't lose the
+    // border to rounding.
+    let clip = |original: Point<f64>, (t, border): (f64, Option<(usize, f64)>)| {
+        let Some((axis, value)) = border else {
+            return original;
+        };
+        let mut point = (start * 0.5 + half * t) * 2.0;
+        if axis == 0 {
+            point.x = value;
+        } else {
+            point.y = value;
+        }
+        point
+    };
+    Some((clip(start, enter), clip(end, leave)))
+}
+
+// `f64::fract` is negative for negative numbers; Wu's algorithm needs the distance to the
+// floor.
+trait FractPositive {
+    fn fract_positive(self) -> f64;
+}
+
+impl FractPositive for f64 {
+    fn fract_positive(self) -> f64 {
+        self - self.floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(x1: i32, y1: i32, x2: i32, y2: i32) -> Line {
+        Line::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    fn points(coordinates: &[(i32, i32)]) -> Vec<Point<i32>> {
+        coordinates.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    // Every line between two points of a small grid, in all octants.
+    fn all_lines() -> Vec<Line> {
+        let range = -6..=6;
+        let mut lines = Vec::new();
+        for x1 in range.clone() {
+            for y1 in range.clone() {
+                for x2 in range.clone() {
+                    for y2 in range.clone() {
+                        lines.push(line(x1, y1, x2, y2));
+                    }
+                }
+            }
+        }
+        lines
+    }
+
+    #[test]
+    fn draws_bresenham_lines() {
+        assert_eq!(
+            line(0, 0, 5, 2).pixels().collect::<Vec<_>>(),
+            points(&[(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 2)])
+        );
+        assert_eq!(
+            line(0, 0, -2, -5).pixels().collect::<Vec<_>>(),
+            points(&[(0, 0), (0, -1), (-1, -2), (-1, -3), (-2, -4), (-2, -5)])
+        );
+        assert_eq!(
+            line(3, 3, 3, 3).pixels().collect::<Vec<_>>(),
+            points(&[(3, 3)])
+        );
+    }
+
+    #[test]
+    fn bresenham_pixels_are_the_nearest_to_the_line() {
+        for segment in all_lines() {
+            let pixels: Vec<Point<i32>> = segment.pixels().collect();
+            let d = segment.direction();
+            let major = d.x.abs().max(d.y.abs());
+
+            assert_eq!(pixels.len(), major as usize + 1);
+            assert_eq!(pixels.first(), Some(&segment.start));
+            assert_eq!(pixels.last(), Some(&segment.end));
+            for pair in pixels.windows(2) {
+                let step = pair[1] - pair[0];
+                assert!(step.x.abs() <= 1 && step.y.abs() <= 1 && step != Point::new(0, 0));
+            }
+            // At most half a pixel off the line along the shorter axis, i.e. the distance
+            // |d x (pixel - start)| / major is at most 1/2.
+            for &pixel in &pixels {
+                assert!(2 * d.cross((pixel - segment.start).widen()).abs() <= major);
+            }
+        }
+    }
+
+    #[test]
+    fn handles_extreme_coordinates() {
+        let long = line(i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+        assert_eq!(long.pixels().len(), 1 << 32);
+
+        let max = i32::MAX;
+        assert_eq!(
+            line(max - 3, 0, max, 1).pixels().collect::<Vec<_>>(),
+            points(&[(max - 3, 0), (max - 2, 0), (max - 1, 1), (max, 1)])
+        );
+    }
+
+    #[test]
+    fn dda_matches_bresenham_without_ties() {
+        for segment in all_lines() {
+            let d = segment.direction();
+            // With an odd length along the longer axis the line never passes exactly
+            // halfway between two pixels, where the two algorithms may round differently.
+            if d.x.abs().max(d.y.abs()) % 2 == 1 {
+                assert_eq!(
+                    segment.pixels_dda().collect::<Vec<_>>(),
+                    segment.pixels().collect::<Vec<_>>()
+                );
+            }
+            assert_eq!(segment.pixels_dda().len(), segment.pixels().len());
+        }
+
+        let float = Line::new(Point::new(0.2, 0.4), Point::new(2.6, 1.4));
+        assert_eq!(
+            float.pixels_dda().collect::<Vec<_>>(),
+            points(&[(0, 0), (1, 1), (2, 1), (3, 1)])
+        );
+    }
+
+    #[test]
+    fn dda_skips_non_finite_lines() {
+        for end in [f64::NAN, f64::INFINITY] {
+            let segment = Line::new(Point::new(0.0, 0.0), Point::new(end, 1.0));
+            assert_eq!(segment.pixels_dda().next(), None);
+            assert_eq!(segment.pixels_dda().len(), 0);
+        }
+    }
+
+    #[test]
+    fn float_lines_are_clipped_to_the_range_of_pixels() {
+        let (min, max) = (i32::MIN, i32::MAX);
+        let float =
+            |x1: f64, y1: f64, x2: f64, y2: f64| Line::new(Point::new(x1, y1), Point::new(x2, y2));
+
+        let leaving = float(f64::from(max) - 2.0, 0.0, 1e300, 0.0);
+        assert_eq!(
+            leaving.pixels_dda().collect::<Vec<_>>(),
+            points(&[(max - 2, 0), (max - 1, 0), (max, 0)])
+        );
+        assert_eq!(
+            leaving.pixels_antialiased().collect::<Vec<_>>(),
+            vec![
+                (Point::new(max - 2, 0), 0.5),
+                (Point::new(max - 1, 0), 1.0),
+                (Point::new(max, 0), 0.5),
+            ]
+        );
+
+        // Both ends are finite, but the distance between them isn't.
+        let widest = float(-f64::MAX, 0.0, f64::MAX, 0.0);
+        let mut pixels = widest.pixels_dda();
+        assert_eq!(pixels.len(), 1 << 32);
+        assert_eq!(pixels.next(), Some(Point::new(min, 0)));
+        assert_eq!(pixels.next(), Some(Point::new(min + 1, 0)));
+        let mut pixels = widest.pixels_antialiased();
+        assert_eq!(pixels.next(), Some((Point::new(min, 0), 0.5)));
+        assert_eq!(pixels.next(), Some((Point::new(min + 1, 0), 1.0)));
+
+        let outside = float(0.0, 1e10, 5.0, 1e10);
+        assert_eq!(outside.pixels_dda().next(), None);
+        assert_eq!(outside.pixels_antialiased().next(), None);
+    }
+
+    #[test]
+    fn antialiased_pixels_are_produced_lazily() {
+        let (min, max) = (i32::MIN, i32::MAX);
+        let mut pixels = line(max, 0, min, 0).pixels_antialiased();
+        assert_eq!(pixels.next(), Some((Point::new(max, 0), 0.5)));
+        assert_eq!(pixels.next(), Some((Point::new(max - 1, 0), 1.0)));
+
+        let infinite = Line::new(Point::new(0.0, 0.0), Point::new(f64::INFINITY, 0.0));
+        assert_eq!(infinite.pixels_antialiased().next(), None);
+    }
+
+    #[test]
+    fn antialiased_lines_share_coverage_between_pixels() {
+        assert_eq!(
+            line(0, 0, 4, 0).pixels_antialiased().collect::<Vec<_>>(),
+            vec![
+                (Point::new(0, 0), 0.5),
+                (Point::new(1, 0), 1.0),
+                (Point::new(2, 0), 1.0),
+                (Point::new(3, 0), 1.0),
+                (Point::new(4, 0), 0.5),
+            ]
+        );
+
+        let half_way = Line::new(Point::new(0.0, 0.5), Point::new(4.0, 0.5));
+        let pixels: Vec<_> = half_way.pixels_antialiased().collect();
+        assert!(pixels.contains(&(Point::new(2, 0), 0.5)));
+        assert!(pixels.contains(&(Point::new(2, 1), 0.5)));
+
+        // Every inner column (row, for steep lines) is covered exactly once in total.
+        for segment in [line(0, 0, 7, 3), line(0, 0, -3, 7), line(5, 5, -4, 1)] {
+            let steep = segment.direction().y.abs() > segment.direction().x.abs();
+            let pixels: Vec<_> = segment.pixels_antialiased().collect();
+            let (first, last) = (pixels[0].0, pixels[pixels.len() - 1].0);
+            assert_eq!(first, segment.start);
+            assert_eq!(last, segment.end);
+            let column = |point: Point<i32>| if steep { point.y } else { point.x };
+            let (low, high) = {
+                let (a, b) = (column(first), column(last));
+                (a.min(b), a.max(b))
+            };
+            for c in low + 1..high {
+                let total: f64 = pixels
+                    .iter()
+                    .filter(|(pixel, _)| column(*pixel) == c)
+                    .map(|(_, coverage)| coverage)
+                    .sum();
+                assert!((total - 1.0).abs() < 1e-9, "column {} of {:?}", c, segment);
+            }
+        }
+    }
+}
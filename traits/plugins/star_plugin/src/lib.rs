@@ -0,0 +1,114 @@
+// A shape kind that lives outside of the traits crate: a star with `points` tips. Built as
+// a shared library and registered at runtime as `star <points> <outer radius> <inner radius>`.
+use std::f64::consts::PI;
+
+use serde_json::{Map, Value};
+use traits::collision::Outline;
+use traits::registry::{expect_params, integer_param};
+use traits::visitor::ShapeVisitor;
+use traits::{
+    BuildError, Canvas, Draw, OtherTrait, Polygon, Shape, ShapeRegistry, SvgStyle, ToJson, ToSvg,
+};
+
+// The geometry is delegated to the star's outline polygon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Star {
+    points: u32,
+    outer_radius: f64,
+    inner_radius: f64,
+    polygon: Polygon,
+}
+
+impl Star {
+    pub fn new(points: u32, outer_radius: f64, inner_radius: f64) -> Star {
+        // Tips and notches alternate around the centre, the first tip pointing up.
+        let vertices = (0..points * 2)
+            .map(|i| {
+                let radius = if i % 2 == 0 {
+                    outer_radius
+                } else {
+                    inner_radius
+                };
+                let angle = PI * f64::from(i) / f64::from(points) - PI / 2.0;
+                (radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        Star {
+            points,
+            outer_radius,
+            inner_radius,
+            polygon: Polygon::new(vertices),
+        }
+    }
+}
+
+impl Shape for Star {
+    fn area(&self) -> f64 {
+        self.polygon.area()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.polygon.perimeter()
+    }
+
+    fn outline(&self) -> Outline {
+        self.polygon.outline()
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_other(self);
+    }
+}
+
+impl Draw for Star {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        self.polygon.draw_object(canvas);
+    }
+}
+
+impl OtherTrait for Star {}
+
+impl ToSvg for Star {
+    fn svg_size(&self) -> (f64, f64) {
+        self.polygon.svg_size()
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        self.polygon.svg_element(x, y, style)
+    }
+}
+
+impl ToJson for Star {
+    fn json_kind(&self) -> &'static str {
+        "star"
+    }
+
+    fn json_fields(&self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        fields.insert("points".to_owned(), self.points.into());
+        fields.insert("outer_radius".to_owned(), self.outer_radius.into());
+        fields.insert("inner_radius".to_owned(), self.inner_radius.into());
+        fields
+    }
+}
+
+fn register(registry: &mut ShapeRegistry) {
+    registry.register("star", |params| {
+        expect_params(params, 3)?;
+        let points = integer_param(params[0])?;
+        if points < 3 {
+            return Err(BuildError::InvalidParameter(format!(
+                "a star needs at least 3 points, got {}",
+                points
+            )));
+        }
+        if !(0.0 < params[2] && params[2] < params[1]) {
+            return Err(BuildError::InvalidParameter(
+                "the inner radius must be positive and smaller than the outer one".to_owned(),
+            ));
+        }
+        Ok(Box::new(Star::new(points as u32, params[1], params[2])))
+    });
+}
+
+traits::declare_plugin!(register);
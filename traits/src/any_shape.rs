@@ -0,0 +1,166 @@
+// Enum dispatch - a third option next to static dispatch (generics) and dynamic dispatch
+// (`Box<dyn Shape>`). All the shape types are wrapped as variants of one enum, and the
+// enum implements `Shape` by matching on the variant. Like a trait object, one `Vec` can
+// hold a mix of shapes; like static dispatch, the calls are resolved without a vtable and
+// the values don't need to be boxed. The price is that the set of shapes is closed: a new
+// shape type needs a new variant. See `src/bin/dispatch_bench.rs` for a comparison.
+use serde_json::{Map, Value};
+
+use crate::collision::Outline;
+use crate::visitor::ShapeVisitor;
+use crate::{
+    Canvas, Circle, Draw, OtherTrait, Polygon, Rectangle, RegularPolygon, Shape, Square, SvgStyle,
+    ToJson, ToSvg, Triangle,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyShape {
+    Square(Square),
+    Rectangle(Rectangle),
+    Circle(Circle),
+    Triangle(Triangle),
+    RegularPolygon(RegularPolygon),
+    Polygon(Polygon),
+}
+
+impl AnyShape {
+    // The wrapped shape as a trait object, used below to forward the trait methods that
+    // aren't on the hot path.
+    pub fn as_shape(&self) -> &dyn Shape {
+        match self {
+            AnyShape::Square(shape) => shape,
+            AnyShape::Rectangle(shape) => shape,
+            AnyShape::Circle(shape) => shape,
+            AnyShape::Triangle(shape) => shape,
+            AnyShape::RegularPolygon(shape) => shape,
+            AnyShape::Polygon(shape) => shape,
+        }
+    }
+}
+
+impl Shape for AnyShape {
+    fn area(&self) -> f64 {
+        match self {
+            AnyShape::Square(shape) => shape.area(),
+            AnyShape::Rectangle(shape) => shape.area(),
+            AnyShape::Circle(shape) => shape.area(),
+            AnyShape::Triangle(shape) => shape.area(),
+            AnyShape::RegularPolygon(shape) => shape.area(),
+            AnyShape::Polygon(shape) => shape.area(),
+        }
+    }
+
+    fn perimeter(&self) -> f64 {
+        match self {
+            AnyShape::Square(shape) => shape.perimeter(),
+            AnyShape::Rectangle(shape) => shape.perimeter(),
+            AnyShape::Circle(shape) => shape.perimeter(),
+            AnyShape::Triangle(shape) => shape.perimeter(),
+            AnyShape::RegularPolygon(shape) => shape.perimeter(),
+            AnyShape::Polygon(shape) => shape.perimeter(),
+        }
+    }
+
+    fn outline(&self) -> Outline {
+        self.as_shape().outline()
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        self.as_shape().accept(visitor);
+    }
+}
+
+impl Draw for AnyShape {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        self.as_shape().draw_object(canvas);
+    }
+}
+
+impl OtherTrait for AnyShape {
+    fn is_convex(&self) -> bool {
+        self.as_shape().is_convex()
+    }
+
+    fn is_regular(&self) -> bool {
+        self.as_shape().is_regular()
+    }
+
+    fn is_axis_aligned(&self) -> bool {
+        self.as_shape().is_axis_aligned()
+    }
+}
+
+impl ToJson for AnyShape {
+    fn json_kind(&self) -> &'static str {
+        self.as_shape().json_kind()
+    }
+
+    fn json_fields(&self) -> Map<String, Value> {
+        self.as_shape().json_fields()
+    }
+}
+
+impl ToSvg for AnyShape {
+    fn svg_size(&self) -> (f64, f64) {
+        self.as_shape().svg_size()
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        self.as_shape().svg_element(x, y, style)
+    }
+}
+
+impl From<Square> for AnyShape {
+    fn from(shape: Square) -> AnyShape {
+        AnyShape::Square(shape)
+    }
+}
+
+impl From<Rectangle> for AnyShape {
+    fn from(shape: Rectangle) -> AnyShape {
+        AnyShape::Rectangle(shape)
+    }
+}
+
+impl From<Circle> for AnyShape {
+    fn from(shape: Circle) -> AnyShape {
+        AnyShape::Circle(shape)
+    }
+}
+
+impl From<Triangle> for AnyShape {
+    fn from(shape: Triangle) -> AnyShape {
+        AnyShape::Triangle(shape)
+    }
+}
+
+impl From<RegularPolygon> for AnyShape {
+    fn from(shape: RegularPolygon) -> AnyShape {
+        AnyShape::RegularPolygon(shape)
+    }
+}
+
+impl From<Polygon> for AnyShape {
+    fn from(shape: Polygon) -> AnyShape {
+        AnyShape::Polygon(shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enum_dispatch_matches_the_wrapped_shapes() {
+        let shapes: Vec<AnyShape> = vec![
+            Square::new(4).into(),
+            Rectangle::new(8, 6).into(),
+            Circle::new(1.0).into(),
+        ];
+        let areas: Vec<f64> = shapes.iter().map(|shape| shape.area()).collect();
+
+        assert_eq!(areas, vec![16.0, 48.0, std::f64::consts::PI]);
+        assert_eq!(shapes[1].perimeter(), 28.0);
+        assert_eq!(shapes[1].bounding_box().size(), (8.0, 6.0));
+    }
+}
@@ -0,0 +1,125 @@
+// Measures the cost of calling `Shape::area` + `Shape::perimeter` through the three
+// dispatch strategies of the traits crate:
+//
+//   generic - static dispatch, `fn total<T: Shape>(&[T])` monomorphized per shape type
+//   dyn     - dynamic dispatch through `Box<dyn Shape>` trait objects
+//   enum    - matching on the variants of `AnyShape`
+//
+// Run with optimizations, otherwise the numbers say little about real code:
+//
+//     cargo run --release --bin dispatch_bench -- [shapes] [rounds]
+use std::env;
+use std::hint::black_box;
+use std::process;
+use std::time::{Duration, Instant};
+
+use traits::any_shape::AnyShape;
+use traits::{Rectangle, Shape, Square};
+
+const DEFAULT_SHAPES: usize = 2_000_000;
+const DEFAULT_ROUNDS: u32 = 5;
+
+fn total_generic<T: Shape>(shapes: &[T]) -> f64 {
+    shapes
+        .iter()
+        .map(|shape| shape.area() + shape.perimeter())
+        .sum()
+}
+
+fn total_dyn(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes
+        .iter()
+        .map(|shape| shape.area() + shape.perimeter())
+        .sum()
+}
+
+fn total_enum(shapes: &[AnyShape]) -> f64 {
+    shapes
+        .iter()
+        .map(|shape| shape.area() + shape.perimeter())
+        .sum()
+}
+
+// Runs `work` `rounds` times and returns the fastest round, which is the least disturbed
+// by other processes.
+fn fastest<F: FnMut() -> f64>(rounds: u32, mut work: F) -> Duration {
+    (0..rounds)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(work());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn report(name: &str, elapsed: Duration, calls: usize) {
+    println!(
+        "{:<8} {:>10.3} ms  {:>7.3} ns/call",
+        name,
+        elapsed.as_secs_f64() * 1e3,
+        elapsed.as_secs_f64() * 1e9 / calls as f64
+    );
+}
+
+fn parse_arg<T: std::str::FromStr>(arg: Option<String>, default: T, name: &str) -> T {
+    match arg {
+        None => default,
+        Some(text) => text.parse().unwrap_or_else(|_| {
+            eprintln!("invalid {}: `{}`", name, text);
+            process::exit(2);
+        }),
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let count: usize = parse_arg(args.next(), DEFAULT_SHAPES, "shape count");
+    let rounds: u32 = parse_arg(args.next(), DEFAULT_ROUNDS, "round count").max(1);
+
+    // The same shapes in three layouts: half squares and half rectangles, alternating.
+    let side = |i: usize| (i % 100) as i32 + 1;
+    let squares: Vec<Square> = (0..count / 2).map(|i| Square::new(side(i))).collect();
+    let rectangles: Vec<Rectangle> = (0..count - count / 2)
+        .map(|i| Rectangle::new(side(i), side(i + 7)))
+        .collect();
+    let boxed: Vec<Box<dyn Shape>> = (0..count)
+        .map(|i| -> Box<dyn Shape> {
+            if i % 2 == 0 {
+                Box::new(Square::new(side(i / 2)))
+            } else {
+                Box::new(Rectangle::new(side(i / 2), side(i / 2 + 7)))
+            }
+        })
+        .collect();
+    let enums: Vec<AnyShape> = (0..count)
+        .map(|i| {
+            if i % 2 == 0 {
+                Square::new(side(i / 2)).into()
+            } else {
+                Rectangle::new(side(i / 2), side(i / 2 + 7)).into()
+            }
+        })
+        .collect();
+
+    // Each shape gets 2 calls: area and perimeter.
+    let calls = 2 * count;
+    println!(
+        "{} shapes, {} calls per round, best of {} rounds",
+        count, calls, rounds
+    );
+    let generic = fastest(rounds, || {
+        total_generic(black_box(&squares)) + total_generic(black_box(&rectangles))
+    });
+    report("generic", generic, calls);
+    report(
+        "dyn",
+        fastest(rounds, || total_dyn(black_box(&boxed))),
+        calls,
+    );
+    report(
+        "enum",
+        fastest(rounds, || total_enum(black_box(&enums))),
+        calls,
+    );
+}
@@ -0,0 +1,21 @@
+// Command line calculator for the shapes of the traits crate, see `traits::cli` for the
+// commands and exit codes.
+//
+//     cargo run --bin shapes -- area rect 8 6
+//     cargo run --bin shapes -- scene scene.txt --svg scene.svg
+use std::env;
+use std::io;
+use std::process::ExitCode;
+
+use traits::cli;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match cli::run(&args, &mut io::stdout()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("shapes: {}", err);
+            err.exit_code()
+        }
+    }
+}
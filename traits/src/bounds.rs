@@ -0,0 +1,67 @@
+// Axis-aligned bounding boxes. Every shape has one in its own local coordinates (see
+// `Shape::bounding_box`), and groups combine the boxes of their children.
+use crate::shapes::Vertex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Vertex,
+    pub max: Vertex,
+}
+
+impl BoundingBox {
+    pub fn new(min: Vertex, max: Vertex) -> BoundingBox {
+        BoundingBox { min, max }
+    }
+
+    // A box of the given size with its top-left corner at (0, 0).
+    pub fn from_size(width: f64, height: f64) -> BoundingBox {
+        BoundingBox::new((0.0, 0.0), (width, height))
+    }
+
+    // The smallest box containing all the points, or None if there are no points.
+    pub fn from_points(points: &[Vertex]) -> Option<BoundingBox> {
+        let (&first, rest) = points.split_first()?;
+        Some(
+            rest.iter()
+                .fold(BoundingBox::new(first, first), |bbox, &point| {
+                    bbox.union(&BoundingBox::new(point, point))
+                }),
+        )
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.0 - self.min.0
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.1 - self.min.1
+    }
+
+    pub fn size(&self) -> (f64, f64) {
+        (self.width(), self.height())
+    }
+
+    // Corners in clockwise order (y grows downwards), starting at the top-left.
+    pub fn corners(&self) -> [Vertex; 4] {
+        [
+            self.min,
+            (self.max.0, self.min.1),
+            self.max,
+            (self.min.0, self.max.1),
+        ]
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox::new(
+            (self.min.0.min(other.min.0), self.min.1.min(other.min.1)),
+            (self.max.0.max(other.max.0), self.max.1.max(other.max.1)),
+        )
+    }
+
+    pub fn translate(&self, dx: f64, dy: f64) -> BoundingBox {
+        BoundingBox::new(
+            (self.min.0 + dx, self.min.1 + dy),
+            (self.max.0 + dx, self.max.1 + dy),
+        )
+    }
+}
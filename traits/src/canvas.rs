@@ -0,0 +1,287 @@
+// A Canvas is a fixed width x height grid of character cells. Shapes implementing the
+// `Draw` trait rasterize themselves into it, and the finished grid can be printed to the
+// terminal or inspected cell-by-cell (which is what the tests below do).
+//
+// Coordinates are (x, y) with (0, 0) at the top-left corner, x growing to the right and
+// y growing downwards. Anything drawn outside the grid is simply clipped.
+use std::fmt;
+
+use crate::Draw;
+
+pub const EMPTY_CELL: char = '.';
+pub const INK_CELL: char = '#';
+
+// Whether shapes should be drawn as solid blocks or only their border cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawMode {
+    Filled,
+    Outline,
+}
+
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<char>,
+    // Offset applied to every drawing call, used to place shapes on the canvas.
+    origin: (i32, i32),
+    mode: DrawMode,
+    ink: char,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize) -> Canvas {
+        Canvas {
+            width,
+            height,
+            cells: vec![EMPTY_CELL; width * height],
+            origin: (0, 0),
+            mode: DrawMode::Filled,
+            ink: INK_CELL,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    pub fn set_origin(&mut self, x: i32, y: i32) {
+        self.origin = (x, y);
+    }
+
+    pub fn mode(&self) -> DrawMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: DrawMode) {
+        self.mode = mode;
+    }
+
+    pub fn ink(&self) -> char {
+        self.ink
+    }
+
+    pub fn set_ink(&mut self, ink: char) {
+        self.ink = ink;
+    }
+
+    // Returns the cell at absolute canvas coordinates, or None if out of bounds.
+    pub fn cell(&self, x: usize, y: usize) -> Option<char> {
+        if x < self.width && y < self.height {
+            Some(self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(EMPTY_CELL);
+    }
+
+    // Sets a single cell relative to the current origin. Cells falling outside of the
+    // grid are ignored.
+    pub fn plot(&mut self, x: i32, y: i32) {
+        let (left, top) = self.absolute(x, y);
+        self.plot_absolute(left, top);
+    }
+
+    // Absolute canvas coordinates of a point relative to the origin. They are i64 because
+    // the sum can leave the range of an i32.
+    fn absolute(&self, x: i32, y: i32) -> (i64, i64) {
+        (
+            i64::from(self.origin.0) + i64::from(x),
+            i64::from(self.origin.1) + i64::from(y),
+        )
+    }
+
+    // Sets a cell given in absolute canvas coordinates, ignoring the origin.
+    pub(crate) fn plot_absolute(&mut self, x: i64, y: i64) {
+        let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) else {
+            return;
+        };
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = self.ink;
+        }
+    }
+
+    // Draws an axis-aligned rectangle of `width` x `height` cells whose top-left corner
+    // is at (x, y) relative to the origin. Honors the current draw mode. Rectangles with
+    // a non-positive side have no cells and draw nothing.
+    pub fn draw_rect(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        // Only visit the cells that land on the canvas, so that huge or off-canvas
+        // rectangles cost no more than the canvas itself.
+        let (columns, rows) = (
+            visible_offsets(self.origin.0, x, width, self.width),
+            visible_offsets(self.origin.1, y, height, self.height),
+        );
+        let (left, top) = self.absolute(x, y);
+        for dy in rows {
+            for dx in columns.clone() {
+                let on_border = dx == 0 || dy == 0 || dx == width - 1 || dy == height - 1;
+                if self.mode == DrawMode::Filled || on_border {
+                    self.plot_absolute(left + i64::from(dx), top + i64::from(dy));
+                }
+            }
+        }
+    }
+
+    // Draws an arbitrary region inside a `width` x `height` box relative to the origin.
+    // A cell belongs to the region when `contains` is true for the cell's centre, given in
+    // box-local coordinates. In outline mode only cells with a 4-neighbour outside the
+    // region are drawn. Like `draw_rect`, only the cells on the canvas are visited, while
+    // the border test still looks at neighbours in the whole box.
+    pub fn draw_region<F>(&mut self, width: i32, height: i32, contains: F)
+    where
+        F: Fn(f64, f64) -> bool,
+    {
+        let inside = |dx: i32, dy: i32| {
+            dx >= 0
+                && dy >= 0
+                && dx < width
+                && dy < height
+                && contains(f64::from(dx) + 0.5, f64::from(dy) + 0.5)
+        };
+        let (columns, rows) = (
+            visible_offsets(self.origin.0, 0, width.max(0), self.width),
+            visible_offsets(self.origin.1, 0, height.max(0), self.height),
+        );
+        for dy in rows {
+            for dx in columns.clone() {
+                if !inside(dx, dy) {
+                    continue;
+                }
+                let on_border = !inside(dx - 1, dy)
+                    || !inside(dx + 1, dy)
+                    || !inside(dx, dy - 1)
+                    || !inside(dx, dy + 1);
+                if self.mode == DrawMode::Filled || on_border {
+                    self.plot(dx, dy);
+                }
+            }
+        }
+    }
+
+    // Places an object on the canvas with its top-left corner at (x, y) and draws it
+    // with the given mode. The canvas' previous origin and mode are restored afterwards.
+    pub fn place(&mut self, object: &dyn Draw, x: i32, y: i32, mode: DrawMode) {
+        let saved = (self.origin, self.mode);
+        self.origin = (x, y);
+        self.mode = mode;
+        object.draw_object(self);
+        (self.origin, self.mode) = saved;
+    }
+
+    // Renders the grid as lines of text, one line per row.
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+// The offsets in 0..len along one axis whose cells, starting at `start` relative to
+// `origin`, fall inside a canvas side of `size` cells.
+fn visible_offsets(origin: i32, start: i32, len: i32, size: usize) -> std::ops::Range<i32> {
+    let first = i64::from(origin) + i64::from(start);
+    let size = i64::try_from(size).unwrap_or(i64::MAX);
+    let lo = (-first).clamp(0, i64::from(len));
+    let hi = (size.saturating_sub(first)).clamp(lo, i64::from(len));
+    // Both bounds lie within 0..=len, so they fit in an i32.
+    lo as i32..hi as i32
+}
+
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            let line: String = row.iter().collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, Rectangle, Shape, Square};
+
+    fn rows(canvas: &Canvas) -> Vec<String> {
+        canvas.render().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn filled_square_covers_every_cell() {
+        let mut canvas = Canvas::new(5, 4);
+        canvas.place(&Square::new(3), 1, 1, DrawMode::Filled);
+
+        assert_eq!(rows(&canvas), vec![".....", ".###.", ".###.", ".###."]);
+    }
+
+    #[test]
+    fn outline_rectangle_leaves_interior_empty() {
+        let mut canvas = Canvas::new(4, 3);
+        canvas.place(&Rectangle::new(4, 3), 0, 0, DrawMode::Outline);
+
+        assert_eq!(rows(&canvas), vec!["####", "#..#", "####"]);
+        assert_eq!(canvas.cell(1, 1), Some(EMPTY_CELL));
+        assert_eq!(canvas.cell(4, 0), None);
+    }
+
+    #[test]
+    fn shapes_are_clipped_at_the_edges() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.place(&Square::new(4), -2, 2, DrawMode::Filled);
+
+        assert_eq!(rows(&canvas), vec!["...", "...", "##."]);
+    }
+
+    #[test]
+    fn huge_rectangles_only_visit_the_canvas() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.set_mode(DrawMode::Outline);
+        canvas.draw_rect(-1, 1, i32::MAX, i32::MAX);
+        canvas.draw_rect(i32::MAX - 1, i32::MIN, i32::MAX, i32::MAX);
+
+        assert_eq!(rows(&canvas), vec!["...", "###", "..."]);
+
+        // Right of i32::MAX relative to the origin, but on the canvas.
+        let mut canvas = Canvas::new(3, 1);
+        canvas.set_origin(-i32::MAX, 0);
+        canvas.draw_rect(i32::MAX - 1, 0, 3, 1);
+        assert_eq!(rows(&canvas), vec!["##."]);
+    }
+
+    #[test]
+    fn huge_regions_only_visit_the_canvas() {
+        // The canvas shows the top of the circle, whose outline is flat at this size.
+        let mut canvas = Canvas::new(4, 4);
+        canvas.place(&Circle::new(20000.0), -20000, 0, DrawMode::Outline);
+        assert_eq!(rows(&canvas), vec!["####", "....", "....", "...."]);
+
+        canvas.place(&Circle::new(20000.0), -20000, 0, DrawMode::Filled);
+        assert_eq!(rows(&canvas), vec!["####", "####", "####", "####"]);
+    }
+
+    #[test]
+    fn scene_of_trait_objects_renders_each_shape() {
+        let scene: Vec<(Box<dyn Shape>, (i32, i32))> = vec![
+            (Box::new(Square::new(2)), (0, 0)),
+            (Box::new(Rectangle::new(3, 2)), (3, 1)),
+        ];
+        let mut canvas = Canvas::new(6, 3);
+        for (shape, (x, y)) in &scene {
+            canvas.place(shape.as_ref(), *x, *y, DrawMode::Filled);
+        }
+
+        assert_eq!(rows(&canvas), vec!["##....", "##.###", "...###"]);
+        assert_eq!(canvas.origin(), (0, 0));
+    }
+}
@@ -0,0 +1,128 @@
+// Capability traits: markers that say what kind of geometry a shape type has, so generic
+// functions can bound on them the same way `share_properties` bounds on `Shape`.
+//
+//   - `Convex`: the segment between any two points of the shape stays inside it.
+//   - `Regular`: all sides and all angles are equal (circles count, with infinitely many
+//     sides). Regular shapes are convex.
+//   - `AxisAligned`: the shape is exactly its bounding box. Such shapes are convex too.
+//
+// The markers pick faster algorithms at compile time: `contains_point_aligned` only
+// accepts `AxisAligned` shapes and is a handful of comparisons, and
+// `contains_shape_convex` skips the edge crossing tests that concave outlines need.
+// Passing a `Polygon` to either is a compile error rather than a wrong answer.
+//
+// Trait objects don't carry these bounds, so `OtherTrait` mirrors them as runtime tags
+// (`is_convex` and so on) for code working with `Box<dyn Shape>`.
+use crate::collision::{Outline, Placed};
+use crate::shapes::Vertex;
+use crate::Shape;
+
+pub trait Convex: Shape {}
+
+pub trait Regular: Convex {
+    // Distance from the centre to the vertices.
+    fn circumradius(&self) -> f64;
+    // Distance from the centre to the middle of the sides.
+    fn inradius(&self) -> f64;
+}
+
+pub trait AxisAligned: Convex {}
+
+// O(1) point test: the bounding box is the whole shape, so no outline is built.
+pub fn contains_point_aligned<T: AxisAligned>(shape: &T, position: Vertex, (x, y): Vertex) -> bool {
+    let (width, height) = shape.svg_size();
+    let (left, top) = position;
+    x >= left && x <= left + width && y >= top && y <= top + height
+}
+
+// Whether `inner` lies inside `outer` at `position`. The outer shape is convex, so when
+// it contains all the vertices of a polygon it contains the edges between them as well.
+pub fn contains_shape_convex<T: Convex>(outer: &T, position: Vertex, inner: &Placed) -> bool {
+    let outline = outer.outline().translate(position.0, position.1);
+    match inner.outline() {
+        Outline::Polygon(vertices) => vertices.iter().all(|&v| outline.contains_point(v)),
+        inner => outline.contains(&inner),
+    }
+}
+
+// The ratio between the circumradius and the inradius, 1 for a circle and growing as
+// a regular shape gets fewer sides.
+pub fn roundness<T: Regular>(shape: &T) -> f64 {
+    shape.circumradius() / shape.inradius()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision;
+    use crate::{Circle, OtherTrait, Polygon, Rectangle, RegularPolygon, Square, Triangle};
+
+    // Compile-time bounds and runtime tags have to agree.
+    fn check_convex<T: Convex>(shape: &T) {
+        assert!(shape.is_convex());
+    }
+
+    fn check_regular<T: Regular>(shape: &T) {
+        assert!(shape.is_regular() && shape.is_convex());
+    }
+
+    fn check_aligned<T: AxisAligned>(shape: &T) {
+        assert!(shape.is_axis_aligned() && shape.is_convex());
+    }
+
+    #[test]
+    fn markers_match_runtime_tags() {
+        check_aligned(&Square::new(2));
+        check_regular(&Square::new(2));
+        check_aligned(&Rectangle::new(2, 3));
+        check_regular(&Circle::new(1.0));
+        check_convex(&Triangle::new((0.0, 0.0), (1.0, 0.0), (0.0, 1.0)));
+        check_regular(&RegularPolygon::new(5, 1.0));
+
+        let concave = Polygon::new(vec![(0.0, 0.0), (2.0, 0.0), (1.0, 1.0), (2.0, 2.0)]);
+        assert!(!concave.is_convex() && !concave.is_regular() && !concave.is_axis_aligned());
+        assert!(!Rectangle::new(2, 3).is_regular());
+    }
+
+    #[test]
+    fn aligned_fast_path_agrees_with_outlines() {
+        let rectangle = Rectangle::new(4, 2);
+        let placed = Placed::new(&rectangle, 1.0, 1.0);
+        for i in 0..=14 {
+            for j in 0..=8 {
+                let point = (f64::from(i) * 0.5, f64::from(j) * 0.5);
+                assert_eq!(
+                    contains_point_aligned(&rectangle, (1.0, 1.0), point),
+                    collision::contains_point(&placed, point),
+                    "{:?}",
+                    point
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn convex_containment_agrees_with_general_test() {
+        let hexagon = RegularPolygon::new(6, 4.0);
+        let outer = Placed::new(&hexagon, 0.0, 0.0);
+        let triangle = Triangle::new((0.0, 0.0), (2.0, 0.0), (0.0, 2.0));
+        let circle = Circle::new(1.0);
+        for (x, y) in [(3.0, 3.0), (0.0, 0.0), (5.0, 2.0), (2.0, 4.0), (6.0, 6.0)] {
+            for inner in [Placed::new(&triangle, x, y), Placed::new(&circle, x, y)] {
+                assert_eq!(
+                    contains_shape_convex(&hexagon, (0.0, 0.0), &inner),
+                    collision::contains_shape(&outer, &inner)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn regular_shapes_know_their_radii() {
+        assert_eq!(roundness(&Circle::new(3.0)), 1.0);
+        assert!((roundness(&Square::new(2)) - 2f64.sqrt()).abs() < 1e-12);
+        // A regular hexagon's circumradius equals its side length.
+        assert!((RegularPolygon::new(6, 2.0).circumradius() - 2.0).abs() < 1e-12);
+        assert!(roundness(&RegularPolygon::new(3, 1.0)) > roundness(&RegularPolygon::new(8, 1.0)));
+    }
+}
@@ -0,0 +1,326 @@
+// The `shapes` command line calculator (src/bin/shapes.rs). The argument handling lives
+// here in the library, writing to any `io::Write`, so the tests below can run commands
+// without spawning a process.
+//
+// Shapes are built through the `ShapeRegistry` and scene files through the parser, i.e.
+// through the same validated constructors as the rest of the crate.
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use serde_json::{json, Value};
+
+use crate::parser::{self, SceneError};
+use crate::svg::{self, SvgStyle};
+use crate::{BuildError, Shape, ShapeRegistry};
+
+pub const USAGE: &str = "\
+usage: shapes [--json] <command>
+
+commands:
+  area <kind> <params...>        area of one shape, e.g. `shapes area rect 8 6`
+  perimeter <kind> <params...>   perimeter of one shape
+  info <kind> <params...>        area, perimeter and bounding box of one shape
+  scene <file> [--svg <out>]     measure every shape of a scene file, optionally
+                                 writing the scene as SVG (scene files only know
+                                 `square <side>` and `rect <length>x<breadth>`)
+  kinds                          list the shape kinds
+
+exit status: 0 on success, 1 for invalid shapes or scene files, 2 for usage errors,
+3 when a file can't be read or written";
+
+#[derive(Debug)]
+pub enum CliError {
+    // Bad command line: unknown command, missing or malformed arguments.
+    Usage(String),
+    Build(BuildError),
+    Scene(SceneError),
+    Io(io::Error),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            CliError::Build(_) | CliError::Scene(SceneError::Parse(_)) => 1,
+            CliError::Usage(_) => 2,
+            CliError::Io(_) | CliError::Scene(SceneError::Io(_)) => 3,
+        })
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}", message),
+            CliError::Build(err) => write!(f, "invalid shape: {}", err),
+            CliError::Scene(err) => write!(f, "{}", err),
+            CliError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for CliError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CliError::Usage(_) => None,
+            CliError::Build(err) => Some(err),
+            CliError::Scene(err) => Some(err),
+            CliError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<BuildError> for CliError {
+    fn from(err: BuildError) -> CliError {
+        CliError::Build(err)
+    }
+}
+
+impl From<SceneError> for CliError {
+    fn from(err: SceneError) -> CliError {
+        CliError::Scene(err)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> CliError {
+        CliError::Io(err)
+    }
+}
+
+fn usage(message: &str) -> CliError {
+    CliError::Usage(format!("{}\n\n{}", message, USAGE))
+}
+
+#[derive(Clone, Copy)]
+enum Measure {
+    Area,
+    Perimeter,
+    Info,
+}
+
+// Runs the command given by `args` (without the program name).
+pub fn run(args: &[String], out: &mut dyn Write) -> Result<(), CliError> {
+    let json = args.iter().any(|arg| arg == "--json");
+    let args: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|&arg| arg != "--json")
+        .collect();
+    let registry = ShapeRegistry::with_builtin_shapes();
+
+    match args.as_slice() {
+        ["area", kind, params @ ..] => measure(&registry, kind, params, Measure::Area, json, out),
+        ["perimeter", kind, params @ ..] => {
+            measure(&registry, kind, params, Measure::Perimeter, json, out)
+        }
+        ["info", kind, params @ ..] => measure(&registry, kind, params, Measure::Info, json, out),
+        ["scene", file, rest @ ..] => {
+            let svg_path = match rest {
+                [] => None,
+                ["--svg", path] => Some(*path),
+                _ => return Err(usage("scene takes a file and an optional `--svg <out>`")),
+            };
+            scene(file, svg_path, json, out)
+        }
+        ["kinds"] => {
+            let kinds: Vec<&str> = registry.kinds().collect();
+            if json {
+                writeln!(out, "{}", json!(kinds))?;
+            } else {
+                writeln!(out, "{}", kinds.join("\n"))?;
+            }
+            Ok(())
+        }
+        ["help"] | ["--help"] | ["-h"] => {
+            writeln!(out, "{}", USAGE)?;
+            Ok(())
+        }
+        [] => Err(usage("missing command")),
+        [command, ..] => Err(usage(&format!(
+            "unknown command or arguments `{}`",
+            command
+        ))),
+    }
+}
+
+fn parse_params(params: &[&str]) -> Result<Vec<f64>, CliError> {
+    params
+        .iter()
+        .map(|param| {
+            param
+                .parse::<f64>()
+                .map_err(|_| usage(&format!("`{}` is not a number", param)))
+        })
+        .collect()
+}
+
+fn shape_summary(shape: &dyn Shape) -> Value {
+    let bbox = shape.bounding_box();
+    json!({
+        "kind": shape.json_kind(),
+        "area": shape.area(),
+        "perimeter": shape.perimeter(),
+        "bounding_box": {"width": bbox.width(), "height": bbox.height()},
+    })
+}
+
+fn shape_line(shape: &dyn Shape) -> String {
+    let (width, height) = shape.bounding_box().size();
+    format!(
+        "{}: area {}, perimeter {}, bounding box {} x {}",
+        shape.json_kind(),
+        shape.area(),
+        shape.perimeter(),
+        width,
+        height
+    )
+}
+
+fn measure(
+    registry: &ShapeRegistry,
+    kind: &str,
+    params: &[&str],
+    what: Measure,
+    json: bool,
+    out: &mut dyn Write,
+) -> Result<(), CliError> {
+    let shape = registry.build(kind, &parse_params(params)?)?;
+    let summary = shape_summary(shape.as_ref());
+    match (what, json) {
+        (Measure::Area, false) => writeln!(out, "{}", shape.area())?,
+        (Measure::Perimeter, false) => writeln!(out, "{}", shape.perimeter())?,
+        (Measure::Info, false) => writeln!(out, "{}", shape_line(shape.as_ref()))?,
+        (Measure::Area, true) => writeln!(out, "{}", json!({"area": summary["area"]}))?,
+        (Measure::Perimeter, true) => {
+            writeln!(out, "{}", json!({"perimeter": summary["perimeter"]}))?
+        }
+        (Measure::Info, true) => writeln!(out, "{}", summary)?,
+    }
+    Ok(())
+}
+
+fn scene(
+    file: &str,
+    svg_path: Option<&str>,
+    json: bool,
+    out: &mut dyn Write,
+) -> Result<(), CliError> {
+    let shapes = parser::load_scene(file)?;
+    let total_area: f64 = shapes.iter().map(|shape| shape.area()).sum();
+    let total_perimeter: f64 = shapes.iter().map(|shape| shape.perimeter()).sum();
+    if json {
+        let summaries: Vec<Value> = shapes
+            .iter()
+            .map(|shape| shape_summary(shape.as_ref()))
+            .collect();
+        let report = json!({
+            "shapes": summaries,
+            "total_area": total_area,
+            "total_perimeter": total_perimeter,
+        });
+        writeln!(out, "{}", report)?;
+    } else {
+        for shape in &shapes {
+            writeln!(out, "{}", shape_line(shape.as_ref()))?;
+        }
+        writeln!(
+            out,
+            "{} shapes, total area {}, total perimeter {}",
+            shapes.len(),
+            total_area,
+            total_perimeter
+        )?;
+    }
+    if let Some(path) = svg_path {
+        fs::write(path, svg::scene_to_svg(&shapes, 2.0, &SvgStyle::default()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn run_args(args: &str) -> Result<String, CliError> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        let mut out = Vec::new();
+        run(&args, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn measures_single_shapes() {
+        assert_eq!(run_args("area rect 8 6").unwrap(), "48\n");
+        assert_eq!(run_args("perimeter square 4").unwrap(), "16\n");
+        assert_eq!(
+            run_args("info rectangle 8 6").unwrap(),
+            "rectangle: area 48, perimeter 28, bounding box 8 x 6\n"
+        );
+        assert_eq!(
+            run_args("--json area rect 8 6").unwrap(),
+            "{\"area\":48.0}\n"
+        );
+        let info: Value = serde_json::from_str(&run_args("info square 3 --json").unwrap()).unwrap();
+        assert_eq!(info["bounding_box"]["width"], 3.0);
+        assert_eq!(info["kind"], "square");
+    }
+
+    #[test]
+    fn invalid_input_has_its_own_exit_codes() {
+        let exit_code = |args| run_args(args).unwrap_err().exit_code();
+
+        assert_eq!(exit_code("area rect 8"), ExitCode::from(1));
+        assert_eq!(exit_code("area square -2"), ExitCode::from(1));
+        assert_eq!(exit_code("area hexagon 1"), ExitCode::from(1));
+        assert_eq!(exit_code("area rect 8 six"), ExitCode::from(2));
+        assert_eq!(exit_code("volume cube 2"), ExitCode::from(2));
+        assert_eq!(exit_code(""), ExitCode::from(2));
+        assert_eq!(exit_code("scene /nonexistent/scene.txt"), ExitCode::from(3));
+    }
+
+    #[test]
+    fn negative_and_non_finite_dimensions_are_invalid_shapes() {
+        let exit_code = |args| run_args(args).unwrap_err().exit_code();
+
+        assert_eq!(exit_code("area circle -3"), ExitCode::from(1));
+        assert_eq!(exit_code("info regular_polygon 3 -2"), ExitCode::from(1));
+        assert_eq!(exit_code("--json area circle nan"), ExitCode::from(1));
+        assert_eq!(exit_code("info circle inf"), ExitCode::from(1));
+        assert_eq!(exit_code("area triangle 0 0 4 0 0 -inf"), ExitCode::from(1));
+        assert_eq!(exit_code("area square 1e10"), ExitCode::from(1));
+        assert!(run_args("area circle -3")
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid shape: radius must be a finite, non-negative number"));
+    }
+
+    #[test]
+    fn measures_scenes_and_writes_svg() {
+        let dir = env::temp_dir().join(format!("shapes-cli-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let scene_file = dir.join("scene.txt");
+        let svg_file = dir.join("scene.svg");
+        fs::write(&scene_file, "square 4\nrect 8x6 # a comment\n").unwrap();
+
+        let output = run_args(&format!(
+            "scene {} --svg {}",
+            scene_file.display(),
+            svg_file.display()
+        ))
+        .unwrap();
+        assert!(output.ends_with("2 shapes, total area 64, total perimeter 44\n"));
+        assert!(fs::read_to_string(&svg_file).unwrap().starts_with("<svg"));
+
+        fs::write(&scene_file, "square 4\ncube 2\n").unwrap();
+        let err = run_args(&format!("scene {}", scene_file.display())).unwrap_err();
+        assert_eq!(err.exit_code(), ExitCode::from(1));
+        assert!(err.to_string().starts_with("line 2"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,370 @@
+// Collision and containment queries between positioned shapes, e.g. for hit-testing.
+//
+// Every shape describes its exact geometry through `Shape::outline`, in its local
+// coordinates (bounding box top-left at (0, 0)). A `Placed` shape moves that outline to a
+// position in 2D. Rectangle-rectangle checks are exact axis-aligned tests, circle-circle
+// checks compare distances and every other combination uses the separating axis theorem.
+//
+// Shapes "intersect" when their interiors overlap; shapes that only touch along an edge
+// or at a corner do not. The separating axis test assumes convex shapes, so a concave
+// polygon may be reported as intersecting a shape that only overlaps its convex hull.
+use crate::bounds::BoundingBox;
+use crate::shapes::{polygon_contains, Vertex};
+use crate::Shape;
+
+// The exact geometry of a shape.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outline {
+    // An axis-aligned rectangle.
+    Rect(BoundingBox),
+    Circle { center: Vertex, radius: f64 },
+    // A simple polygon, vertices in order.
+    Polygon(Vec<Vertex>),
+}
+
+impl Outline {
+    pub fn translate(&self, dx: f64, dy: f64) -> Outline {
+        match self {
+            Outline::Rect(bbox) => Outline::Rect(bbox.translate(dx, dy)),
+            Outline::Circle { center, radius } => Outline::Circle {
+                center: (center.0 + dx, center.1 + dy),
+                radius: *radius,
+            },
+            Outline::Polygon(vertices) => {
+                Outline::Polygon(vertices.iter().map(|&(x, y)| (x + dx, y + dy)).collect())
+            }
+        }
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        match self {
+            Outline::Rect(bbox) => *bbox,
+            Outline::Circle { center, radius } => BoundingBox::new(
+                (center.0 - radius, center.1 - radius),
+                (center.0 + radius, center.1 + radius),
+            ),
+            Outline::Polygon(vertices) => BoundingBox::from_points(vertices)
+                .unwrap_or_else(|| BoundingBox::from_size(0.0, 0.0)),
+        }
+    }
+
+    // Rectangles and polygons as a list of vertices, None for circles.
+    fn vertices(&self) -> Option<Vec<Vertex>> {
+        match self {
+            Outline::Rect(bbox) => Some(bbox.corners().to_vec()),
+            Outline::Circle { .. } => None,
+            Outline::Polygon(vertices) => Some(vertices.clone()),
+        }
+    }
+
+    pub fn contains_point(&self, (x, y): Vertex) -> bool {
+        match self {
+            Outline::Rect(bbox) => {
+                x >= bbox.min.0 && x <= bbox.max.0 && y >= bbox.min.1 && y <= bbox.max.1
+            }
+            Outline::Circle { center, radius } => distance(*center, (x, y)) <= *radius,
+            Outline::Polygon(vertices) => {
+                polygon_contains(vertices, (x, y))
+                    || edges(vertices).any(|(a, b)| distance_to_segment((x, y), a, b) == 0.0)
+            }
+        }
+    }
+
+    // Distance from the point to the nearest point of the shape, 0 if it is inside.
+    pub fn distance_to_point(&self, point: Vertex) -> f64 {
+        match self {
+            Outline::Rect(bbox) => {
+                let dx = (bbox.min.0 - point.0).max(point.0 - bbox.max.0).max(0.0);
+                let dy = (bbox.min.1 - point.1).max(point.1 - bbox.max.1).max(0.0);
+                dx.hypot(dy)
+            }
+            Outline::Circle { center, radius } => (distance(*center, point) - radius).max(0.0),
+            Outline::Polygon(vertices) => {
+                if polygon_contains(vertices, point) {
+                    0.0
+                } else {
+                    edges(vertices)
+                        .map(|(a, b)| distance_to_segment(point, a, b))
+                        .fold(f64::INFINITY, f64::min)
+                }
+            }
+        }
+    }
+
+    pub fn intersects(&self, other: &Outline) -> bool {
+        match (self, other) {
+            (Outline::Rect(a), Outline::Rect(b)) => {
+                a.min.0 < b.max.0 && b.min.0 < a.max.0 && a.min.1 < b.max.1 && b.min.1 < a.max.1
+            }
+            (
+                Outline::Circle {
+                    center: c1,
+                    radius: r1,
+                },
+                Outline::Circle {
+                    center: c2,
+                    radius: r2,
+                },
+            ) => distance(*c1, *c2) < r1 + r2,
+            _ => !separated(self, other),
+        }
+    }
+
+    // Whether `inner` lies completely inside this outline (touching the border counts
+    // as inside).
+    pub fn contains(&self, inner: &Outline) -> bool {
+        match (self, inner) {
+            (
+                Outline::Circle { center, radius },
+                Outline::Circle {
+                    center: inner_center,
+                    radius: inner_radius,
+                },
+            ) => distance(*center, *inner_center) + inner_radius <= *radius,
+            // A rectangle contains exactly what fits in it.
+            (Outline::Rect(bbox), _) => {
+                let inner = inner.bounding_box();
+                inner.min.0 >= bbox.min.0
+                    && inner.min.1 >= bbox.min.1
+                    && inner.max.0 <= bbox.max.0
+                    && inner.max.1 <= bbox.max.1
+            }
+            // A circle is convex, so it contains a polygon when it contains its vertices.
+            (Outline::Circle { .. }, _) => inner
+                .vertices()
+                .is_some_and(|vertices| vertices.iter().all(|&v| self.contains_point(v))),
+            (Outline::Polygon(outer), Outline::Circle { center, radius }) => {
+                self.contains_point(*center)
+                    && edges(outer).all(|(a, b)| distance_to_segment(*center, a, b) >= *radius)
+            }
+            // The polygon may be concave, so besides all the vertices being inside, no
+            // edge of the inner shape may cross an edge of the outer one.
+            (Outline::Polygon(outer), _) => {
+                let inner = inner.vertices().unwrap_or_default();
+                inner.iter().all(|&v| self.contains_point(v))
+                    && edges(&inner)
+                        .all(|(a, b)| edges(outer).all(|(c, d)| !segments_cross(a, b, c, d)))
+            }
+        }
+    }
+}
+
+// A shape at a position in 2D: the top-left corner of its bounding box is at `position`.
+pub struct Placed<'a> {
+    pub shape: &'a dyn Shape,
+    pub position: Vertex,
+}
+
+impl<'a> Placed<'a> {
+    pub fn new(shape: &'a dyn Shape, x: f64, y: f64) -> Placed<'a> {
+        Placed {
+            shape,
+            position: (x, y),
+        }
+    }
+
+    pub fn outline(&self) -> Outline {
+        self.shape
+            .outline()
+            .translate(self.position.0, self.position.1)
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.shape
+            .bounding_box()
+            .translate(self.position.0, self.position.1)
+    }
+}
+
+pub fn intersects(a: &Placed, b: &Placed) -> bool {
+    // Cheap rejection before the exact test.
+    let (box_a, box_b) = (a.bounding_box(), b.bounding_box());
+    if box_a.max.0 < box_b.min.0
+        || box_b.max.0 < box_a.min.0
+        || box_a.max.1 < box_b.min.1
+        || box_b.max.1 < box_a.min.1
+    {
+        return false;
+    }
+    a.outline().intersects(&b.outline())
+}
+
+pub fn contains_point(shape: &Placed, point: Vertex) -> bool {
+    shape.outline().contains_point(point)
+}
+
+pub fn contains_shape(outer: &Placed, inner: &Placed) -> bool {
+    outer.outline().contains(&inner.outline())
+}
+
+fn distance(a: Vertex, b: Vertex) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+fn cross(o: Vertex, a: Vertex, b: Vertex) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+// Consecutive vertex pairs, including the closing edge from the last to the first.
+fn edges(vertices: &[Vertex]) -> impl Iterator<Item = (Vertex, Vertex)> + '_ {
+    let n = vertices.len();
+    (0..n).map(move |i| (vertices[i], vertices[(i + 1) % n]))
+}
+
+fn distance_to_segment(p: Vertex, a: Vertex, b: Vertex) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return distance(p, a);
+    }
+    let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_squared).clamp(0.0, 1.0);
+    distance(p, (a.0 + t * dx, a.1 + t * dy))
+}
+
+// Whether segments ab and cd properly cross (each one strictly separates the other's
+// end points).
+fn segments_cross(a: Vertex, b: Vertex, c: Vertex, d: Vertex) -> bool {
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+// Projection of an outline onto `axis` as a (min, max) interval.
+fn project(outline: &Outline, axis: Vertex) -> (f64, f64) {
+    let dot = |(x, y): Vertex| x * axis.0 + y * axis.1;
+    match outline {
+        Outline::Circle { center, radius } => {
+            let c = dot(*center);
+            let r = radius * axis.0.hypot(axis.1);
+            (c - r, c + r)
+        }
+        _ => outline
+            .vertices()
+            .unwrap_or_default()
+            .into_iter()
+            .map(dot)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p), hi.max(p))
+            }),
+    }
+}
+
+// Candidate separating axes: the edge normals of polygons, and for circles the direction
+// from the centre to the other shape's closest vertex.
+fn axes(outline: &Outline, other: &Outline) -> Vec<Vertex> {
+    match outline {
+        Outline::Circle { center, .. } => other
+            .vertices()
+            .unwrap_or_default()
+            .into_iter()
+            .min_by(|a, b| distance(*center, *a).total_cmp(&distance(*center, *b)))
+            .map(|closest| vec![(closest.0 - center.0, closest.1 - center.1)])
+            .unwrap_or_default(),
+        _ => edges(&outline.vertices().unwrap_or_default())
+            .map(|(a, b)| (a.1 - b.1, b.0 - a.0))
+            .collect(),
+    }
+}
+
+fn separated(a: &Outline, b: &Outline) -> bool {
+    axes(a, b)
+        .into_iter()
+        .chain(axes(b, a))
+        .filter(|axis| *axis != (0.0, 0.0))
+        .any(|axis| {
+            let (min_a, max_a) = project(a, axis);
+            let (min_b, max_b) = project(b, axis);
+            max_a <= min_b || max_b <= min_a
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, Polygon, Rectangle, Square, Triangle};
+
+    #[test]
+    fn rectangles_use_exact_axis_aligned_tests() {
+        let a = Rectangle::new(4, 2);
+        let b = Square::new(2);
+
+        assert!(intersects(
+            &Placed::new(&a, 0.0, 0.0),
+            &Placed::new(&b, 3.0, 1.0)
+        ));
+        // Sharing an edge is not an overlap.
+        assert!(!intersects(
+            &Placed::new(&a, 0.0, 0.0),
+            &Placed::new(&b, 4.0, 0.0)
+        ));
+        assert!(contains_point(&Placed::new(&a, 1.0, 1.0), (5.0, 3.0)));
+        assert!(!contains_point(&Placed::new(&a, 1.0, 1.0), (5.1, 3.0)));
+    }
+
+    #[test]
+    fn circles_and_polygons_use_separating_axes() {
+        let circle = Circle::new(1.0);
+        let triangle = Triangle::new((0.0, 0.0), (4.0, 0.0), (0.0, 4.0));
+
+        // The circle sits just beyond the hypotenuse, inside the triangle's bounding box.
+        let outside = 2.0 + 1.0 / 2f64.sqrt() + 0.01;
+        assert!(!intersects(
+            &Placed::new(&triangle, 0.0, 0.0),
+            &Placed::new(&circle, outside, outside)
+        ));
+        assert!(intersects(
+            &Placed::new(&triangle, 0.0, 0.0),
+            &Placed::new(&circle, 1.0, 1.0)
+        ));
+        assert!(intersects(
+            &Placed::new(&circle, 0.0, 0.0),
+            &Placed::new(&Circle::new(2.0), 1.5, 0.0)
+        ));
+        assert!(!intersects(
+            &Placed::new(&triangle, 0.0, 0.0),
+            &Placed::new(&Square::new(1), 3.0, 3.0)
+        ));
+    }
+
+    #[test]
+    fn containment() {
+        let big = Square::new(10);
+        let circle = Circle::new(2.0);
+        let l_shape = Polygon::new(vec![
+            (0.0, 0.0),
+            (6.0, 0.0),
+            (6.0, 2.0),
+            (2.0, 2.0),
+            (2.0, 6.0),
+            (0.0, 6.0),
+        ]);
+
+        assert!(contains_shape(
+            &Placed::new(&big, 0.0, 0.0),
+            &Placed::new(&circle, 6.0, 6.0)
+        ));
+        assert!(!contains_shape(
+            &Placed::new(&big, 0.0, 0.0),
+            &Placed::new(&circle, 7.0, 6.0)
+        ));
+        assert!(contains_shape(
+            &Placed::new(&Circle::new(5.0), 0.0, 0.0),
+            &Placed::new(&circle, 3.0, 3.0)
+        ));
+        // All three corners of the triangle are inside the L, but its long edge crosses
+        // the notch, so the triangle is not contained.
+        assert!(!contains_shape(
+            &Placed::new(&l_shape, 0.0, 0.0),
+            &Placed::new(&Triangle::new((0.0, 4.0), (4.0, 0.0), (0.0, 0.0)), 1.0, 1.0)
+        ));
+        assert!(contains_shape(
+            &Placed::new(&l_shape, 0.0, 0.0),
+            &Placed::new(&Rectangle::new(5, 1), 0.5, 0.5)
+        ));
+        assert!(contains_point(&Placed::new(&l_shape, 0.0, 0.0), (1.0, 5.0)));
+        assert!(!contains_point(
+            &Placed::new(&l_shape, 0.0, 0.0),
+            (4.0, 4.0)
+        ));
+    }
+}
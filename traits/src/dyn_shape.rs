@@ -0,0 +1,111 @@
+// Clone and PartialEq for `Box<dyn Shape>` trait objects.
+//
+// Neither trait can be a supertrait of `Shape` directly: `Clone::clone` returns `Self` and
+// `PartialEq::eq` takes another `Self`, and a trait object doesn't know the size or the
+// type of `Self`. `DynShape` wraps them in object-safe methods instead, and the blanket impl
+// below gives them to every shape that derives `Clone` and `PartialEq`. Equality between
+// two trait objects first downcasts the other shape (through `Any`) to the type of the
+// first one, so shapes of different types are never equal.
+//
+// `Debug` needs no such trick since `fmt` only takes `&self`; it is a plain supertrait.
+use std::any::Any;
+
+use crate::Shape;
+
+pub trait DynShape {
+    // Clones the shape into a new box.
+    fn clone_box(&self) -> Box<dyn Shape>;
+
+    // The shape as `Any`, so it can be downcast back to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    // True when `other` has the same concrete type as `self` and compares equal to it.
+    fn eq_dyn(&self, other: &dyn Shape) -> bool;
+}
+
+impl<T> DynShape for T
+where
+    T: Shape + Clone + PartialEq + 'static,
+{
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_dyn(&self, other: &dyn Shape) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<T>()
+            .is_some_and(|other| self == other)
+    }
+}
+
+impl Clone for Box<dyn Shape> {
+    fn clone(&self) -> Box<dyn Shape> {
+        self.clone_box()
+    }
+}
+
+// `Box<dyn Shape>` (and `Vec`s of them) compare through this impl as well.
+impl PartialEq for dyn Shape {
+    fn eq(&self, other: &dyn Shape) -> bool {
+        self.eq_dyn(other)
+    }
+}
+
+// Downcasts a shape trait object back to its concrete type.
+pub fn downcast_ref<T: Shape + 'static>(shape: &dyn Shape) -> Option<&T> {
+    shape.as_any().downcast_ref::<T>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, Group, Rectangle, Square, Transform};
+
+    fn scene() -> Vec<Box<dyn Shape>> {
+        vec![
+            Box::new(Square::new(4)),
+            Box::new(Rectangle::new(8, 6)),
+            Box::new(Group::new().with(Box::new(Circle::new(1.5)), Transform::translate(2.0, 0.0))),
+        ]
+    }
+
+    #[test]
+    fn cloned_scenes_compare_equal() {
+        let original = scene();
+        let mut copy = original.clone();
+        assert_eq!(copy, original);
+
+        copy[1] = Box::new(Rectangle::new(8, 7));
+        assert_ne!(copy, original);
+    }
+
+    #[test]
+    fn shapes_of_different_types_are_never_equal() {
+        // Same area, perimeter and fields, but a square is not a rectangle.
+        let square: Box<dyn Shape> = Box::new(Square::new(4));
+        let rectangle: Box<dyn Shape> = Box::new(Rectangle::new(4, 4));
+
+        assert!(square != rectangle);
+        assert!(square == Box::new(Square::new(4)) as Box<dyn Shape>);
+    }
+
+    #[test]
+    fn debug_output_and_downcasting() {
+        let shapes = scene();
+
+        assert_eq!(format!("{:?}", shapes[0]), "Square { side: 4 }");
+        assert!(format!("{:?}", shapes[2]).starts_with("Group { children: [(Circle"));
+        assert_eq!(
+            downcast_ref::<Rectangle>(shapes[1].as_ref())
+                .unwrap()
+                .length(),
+            8
+        );
+        assert!(downcast_ref::<Circle>(shapes[0].as_ref()).is_none());
+    }
+}
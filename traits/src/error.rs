@@ -0,0 +1,146 @@
+// Errors for invalid shape dimensions and for integer measurements that do not fit in
+// an `i32`. Returned by the validated constructors (`Square::try_new`,
+// `Circle::try_new`, ...) and the checked measurements (`checked_area`,
+// `checked_perimeter`) instead of silently wrapping around.
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeError {
+    // A side was negative. `dimension` names the offending side, e.g. "breadth".
+    NegativeDimension { dimension: &'static str, value: i32 },
+    // A floating-point dimension (e.g. a radius) was negative, NaN or infinite.
+    InvalidDimension { dimension: &'static str, value: f64 },
+    // A vertex coordinate was NaN or infinite.
+    NonFiniteCoordinate(f64),
+    // A polygon with fewer vertices (or sides) than it needs.
+    TooFewVertices { minimum: usize, got: usize },
+    // A group child's scale was zero, negative, NaN or infinite.
+    InvalidScale(f64),
+    // The measurement is too large to be represented as an `i32`.
+    Overflow,
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShapeError::NegativeDimension { dimension, value } => {
+                write!(f, "{} must not be negative, got {}", dimension, value)
+            }
+            ShapeError::InvalidDimension { dimension, value } => write!(
+                f,
+                "{} must be a finite, non-negative number, got {}",
+                dimension, value
+            ),
+            ShapeError::NonFiniteCoordinate(value) => {
+                write!(f, "vertex coordinate {} is not a finite number", value)
+            }
+            ShapeError::TooFewVertices { minimum, got } => {
+                write!(
+                    f,
+                    "a polygon needs at least {} vertices, got {}",
+                    minimum, got
+                )
+            }
+            ShapeError::InvalidScale(value) => {
+                write!(f, "scale must be a finite, positive number, got {}", value)
+            }
+            ShapeError::Overflow => write!(f, "measurement overflows an i32"),
+        }
+    }
+}
+
+impl Error for ShapeError {}
+
+// Returns the value back if it is a valid (non-negative) dimension.
+pub fn check_dimension(dimension: &'static str, value: i32) -> Result<i32, ShapeError> {
+    if value < 0 {
+        Err(ShapeError::NegativeDimension { dimension, value })
+    } else {
+        Ok(value)
+    }
+}
+
+// The floating-point version of `check_dimension`, for radii and side lengths.
+pub fn check_length(dimension: &'static str, value: f64) -> Result<f64, ShapeError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(ShapeError::InvalidDimension { dimension, value })
+    }
+}
+
+// Returns the scale back if it is finite and positive, so that it can be inverted.
+pub fn check_scale(value: f64) -> Result<f64, ShapeError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(ShapeError::InvalidScale(value))
+    }
+}
+
+// Returns the vertex back if both coordinates are finite.
+pub fn check_vertex(vertex: (f64, f64)) -> Result<(f64, f64), ShapeError> {
+    for value in [vertex.0, vertex.1] {
+        if !value.is_finite() {
+            return Err(ShapeError::NonFiniteCoordinate(value));
+        }
+    }
+    Ok(vertex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rectangle, Square};
+
+    #[test]
+    fn rejects_negative_dimensions() {
+        assert!(Square::try_new(0).is_ok());
+        assert_eq!(
+            Square::try_new(-4).err(),
+            Some(ShapeError::NegativeDimension {
+                dimension: "side",
+                value: -4
+            })
+        );
+        assert_eq!(
+            Rectangle::try_new(8, -6).err(),
+            Some(ShapeError::NegativeDimension {
+                dimension: "breadth",
+                value: -6
+            })
+        );
+    }
+
+    #[test]
+    fn checked_measurements() {
+        let square = Square::try_new(4).unwrap();
+        let rectangle = Rectangle::try_new(8, 6).unwrap();
+
+        assert_eq!(square.checked_area(), Ok(16));
+        assert_eq!(square.checked_perimeter(), Ok(16));
+        assert_eq!(rectangle.checked_area(), Ok(48));
+        assert_eq!(rectangle.checked_perimeter(), Ok(28));
+    }
+
+    #[test]
+    fn reports_overflow_instead_of_wrapping() {
+        let square = Square::try_new(46_341).unwrap();
+        let rectangle = Rectangle::try_new(i32::MAX, 1).unwrap();
+
+        assert_eq!(square.checked_area(), Err(ShapeError::Overflow));
+        assert_eq!(
+            Square::try_new(46_340).unwrap().checked_area(),
+            Ok(2_147_395_600)
+        );
+        assert_eq!(rectangle.checked_area(), Ok(i32::MAX));
+        assert_eq!(rectangle.checked_perimeter(), Err(ShapeError::Overflow));
+        assert_eq!(
+            Square::try_new(i32::MAX / 4 + 1)
+                .unwrap()
+                .checked_perimeter(),
+            Err(ShapeError::Overflow)
+        );
+    }
+}
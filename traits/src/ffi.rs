@@ -0,0 +1,312 @@
+// C ABI over the shapes, for tools written in C or C++. The crate is also built as a
+// `cdylib` (libtraits.so), and `build.rs` generates the matching header from the
+// declarations in this file; the copy in c/shapes.h is kept in sync by a test below.
+//
+// Shapes are handed out as opaque `ShapesShape` pointers: C code can only pass them back
+// to these functions and must release each one with `shapes_shape_free`. Every fallible
+// function returns a `ShapesStatus` and writes its result through an out pointer, and no
+// panic crosses the C boundary.
+use std::ffi::c_char;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Rectangle, ShapeError, Square};
+
+// Opaque handle to a shape. Only the integer shapes are exposed, so that measurements are
+// exact and report `SHAPES_STATUS_OVERFLOW` instead of losing precision.
+pub struct ShapesShape {
+    shape: HandleShape,
+}
+
+enum HandleShape {
+    Square(Square),
+    Rectangle(Rectangle),
+}
+
+impl HandleShape {
+    fn checked_area(&self) -> Result<i32, ShapeError> {
+        match self {
+            HandleShape::Square(square) => square.checked_area(),
+            HandleShape::Rectangle(rectangle) => rectangle.checked_area(),
+        }
+    }
+
+    fn checked_perimeter(&self) -> Result<i32, ShapeError> {
+        match self {
+            HandleShape::Square(square) => square.checked_perimeter(),
+            HandleShape::Rectangle(rectangle) => rectangle.checked_perimeter(),
+        }
+    }
+}
+
+// Result of every fallible function, `SHAPES_STATUS_OK` on success.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapesStatus {
+    Ok = 0,
+    // A pointer argument was NULL.
+    NullPointer = 1,
+    // A dimension was negative.
+    NegativeDimension = 2,
+    // A measurement doesn't fit in an `int32_t`.
+    Overflow = 3,
+    // A bug on the Rust side. The out pointer was left untouched.
+    Panic = 4,
+}
+
+impl From<ShapeError> for ShapesStatus {
+    fn from(err: ShapeError) -> ShapesStatus {
+        match err {
+            ShapeError::NegativeDimension { .. } => ShapesStatus::NegativeDimension,
+            ShapeError::Overflow => ShapesStatus::Overflow,
+            // The C API only builds squares and rectangles, whose dimensions are integers.
+            ShapeError::InvalidDimension { .. }
+            | ShapeError::NonFiniteCoordinate(_)
+            | ShapeError::InvalidScale(_)
+            | ShapeError::TooFewVertices { .. } => {
+                unreachable!("not an error of an integer shape: {}", err)
+            }
+        }
+    }
+}
+
+// Runs `f` with the out pointer checked, turning panics into `ShapesStatus::Panic`.
+fn with_out<T, F>(out: *mut T, f: F) -> ShapesStatus
+where
+    F: FnOnce() -> Result<T, ShapesStatus>,
+{
+    if out.is_null() {
+        return ShapesStatus::NullPointer;
+    }
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => {
+            // SAFETY: checked for NULL above; the caller guarantees it is valid to write.
+            unsafe { out.write(value) };
+            ShapesStatus::Ok
+        }
+        Ok(Err(status)) => status,
+        Err(_) => ShapesStatus::Panic,
+    }
+}
+
+fn new_handle(shape: HandleShape) -> *mut ShapesShape {
+    Box::into_raw(Box::new(ShapesShape { shape }))
+}
+
+// Reads the shape behind a handle passed in from C.
+//
+// SAFETY: `shape` must be NULL or a live handle returned by this library.
+unsafe fn shape_ref<'a>(shape: *const ShapesShape) -> Result<&'a HandleShape, ShapesStatus> {
+    shape
+        .as_ref()
+        .map(|handle| &handle.shape)
+        .ok_or(ShapesStatus::NullPointer)
+}
+
+/// Creates a square. On success `*out` receives a handle to free with `shapes_shape_free`.
+#[no_mangle]
+pub extern "C" fn shapes_square_new(side: i32, out: *mut *mut ShapesShape) -> ShapesStatus {
+    with_out(out, || {
+        Ok(new_handle(HandleShape::Square(Square::try_new(side)?)))
+    })
+}
+
+/// Creates a rectangle. On success `*out` receives a handle to free with
+/// `shapes_shape_free`.
+#[no_mangle]
+pub extern "C" fn shapes_rectangle_new(
+    length: i32,
+    breadth: i32,
+    out: *mut *mut ShapesShape,
+) -> ShapesStatus {
+    with_out(out, || {
+        Ok(new_handle(HandleShape::Rectangle(Rectangle::try_new(
+            length, breadth,
+        )?)))
+    })
+}
+
+/// Releases a shape. NULL is ignored.
+///
+/// # Safety
+/// `shape` must be NULL or a handle from this library that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_shape_free(shape: *mut ShapesShape) {
+    if !shape.is_null() {
+        drop(Box::from_raw(shape));
+    }
+}
+
+/// Writes the area of the shape to `*out`, or returns `SHAPES_STATUS_OVERFLOW` if it
+/// doesn't fit in an `int32_t`.
+///
+/// # Safety
+/// `shape` must be NULL or a live handle from this library.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_shape_area(
+    shape: *const ShapesShape,
+    out: *mut f64,
+) -> ShapesStatus {
+    with_out(out, || Ok(f64::from(shape_ref(shape)?.checked_area()?)))
+}
+
+/// Writes the perimeter of the shape to `*out`, or returns `SHAPES_STATUS_OVERFLOW` if it
+/// doesn't fit in an `int32_t`.
+///
+/// # Safety
+/// `shape` must be NULL or a live handle from this library.
+#[no_mangle]
+pub unsafe extern "C" fn shapes_shape_perimeter(
+    shape: *const ShapesShape,
+    out: *mut f64,
+) -> ShapesStatus {
+    with_out(out, || {
+        Ok(f64::from(shape_ref(shape)?.checked_perimeter()?))
+    })
+}
+
+/// A static, NUL-terminated description of a status code. Never NULL. Takes a plain
+/// integer so that any value coming from C is valid; unknown codes get "unknown status".
+#[no_mangle]
+pub extern "C" fn shapes_status_message(status: u32) -> *const c_char {
+    const OK: u32 = ShapesStatus::Ok as u32;
+    const NULL_POINTER: u32 = ShapesStatus::NullPointer as u32;
+    const NEGATIVE_DIMENSION: u32 = ShapesStatus::NegativeDimension as u32;
+    const OVERFLOW: u32 = ShapesStatus::Overflow as u32;
+    const PANIC: u32 = ShapesStatus::Panic as u32;
+    let message: &'static [u8] = match status {
+        OK => b"ok\0",
+        NULL_POINTER => b"unexpected NULL pointer\0",
+        NEGATIVE_DIMENSION => b"negative dimension\0",
+        OVERFLOW => b"measurement overflows\0",
+        PANIC => b"internal error\0",
+        _ => b"unknown status\0",
+    };
+    message.as_ptr().cast()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use std::ptr;
+
+    use super::*;
+
+    #[test]
+    fn handles_round_trip_through_the_c_api() {
+        let mut square = ptr::null_mut();
+        let mut area = 0.0;
+        let mut perimeter = 0.0;
+        assert_eq!(shapes_square_new(3, &mut square), ShapesStatus::Ok);
+        unsafe {
+            assert_eq!(shapes_shape_area(square, &mut area), ShapesStatus::Ok);
+            assert_eq!(
+                shapes_shape_perimeter(square, &mut perimeter),
+                ShapesStatus::Ok
+            );
+            shapes_shape_free(square);
+        }
+        assert_eq!((area, perimeter), (9.0, 12.0));
+    }
+
+    #[test]
+    fn reports_errors_as_status_codes() {
+        let mut rectangle = ptr::null_mut();
+        assert_eq!(
+            shapes_rectangle_new(-1, 2, &mut rectangle),
+            ShapesStatus::NegativeDimension
+        );
+        assert!(rectangle.is_null());
+        assert_eq!(
+            shapes_rectangle_new(1, 2, ptr::null_mut()),
+            ShapesStatus::NullPointer
+        );
+        let mut area = 0.0;
+        assert_eq!(
+            unsafe { shapes_shape_area(ptr::null(), &mut area) },
+            ShapesStatus::NullPointer
+        );
+    }
+
+    #[test]
+    fn reports_overflowing_measurements() {
+        let mut square = ptr::null_mut();
+        let mut area = -1.0;
+        let mut perimeter = -1.0;
+        assert_eq!(shapes_square_new(50_000, &mut square), ShapesStatus::Ok);
+        unsafe {
+            assert_eq!(shapes_shape_area(square, &mut area), ShapesStatus::Overflow);
+            assert_eq!(
+                shapes_shape_perimeter(square, &mut perimeter),
+                ShapesStatus::Ok
+            );
+            shapes_shape_free(square);
+        }
+        assert_eq!((area, perimeter), (-1.0, 200_000.0));
+    }
+
+    #[test]
+    fn describes_any_status_code() {
+        let message = |status| unsafe { CStr::from_ptr(shapes_status_message(status)) };
+
+        assert_eq!(
+            message(ShapesStatus::Overflow as u32),
+            c"measurement overflows"
+        );
+        assert_eq!(message(99), c"unknown status");
+    }
+
+    // The header C users read is checked in; it must match what build.rs generates.
+    #[test]
+    fn checked_in_header_is_up_to_date() {
+        let generated = fs::read_to_string(Path::new(env!("OUT_DIR")).join("shapes.h")).unwrap();
+        let checked_in =
+            fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("c/shapes.h")).unwrap();
+        assert!(
+            generated == checked_in,
+            "c/shapes.h is out of date, copy it from {}/shapes.h",
+            env!("OUT_DIR")
+        );
+    }
+
+    // Compiles c/test_shapes.c against the generated header and the cdylib, then runs it.
+    #[test]
+    fn c_harness_passes() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let c_dir = manifest_dir.join("c");
+        // Cargo builds the cdylib next to the test binary, in target/<profile>/deps.
+        let test_exe = std::env::current_exe().unwrap();
+        let deps_dir = test_exe.parent().unwrap();
+        let library = deps_dir.join(format!(
+            "{}traits{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        ));
+        assert!(library.exists(), "{} was not built", library.display());
+
+        let harness = deps_dir.join("test_shapes");
+        let compiled = Command::new("cc")
+            .args(["-std=c99", "-Wall", "-Werror", "-I", env!("OUT_DIR")])
+            .arg(c_dir.join("test_shapes.c"))
+            .arg("-o")
+            .arg(&harness)
+            .arg("-L")
+            .arg(deps_dir)
+            .arg("-ltraits")
+            .status()
+            .expect("a C compiler is installed");
+        assert!(compiled.success(), "compiling the C harness failed");
+
+        let output = Command::new(&harness)
+            .env("LD_LIBRARY_PATH", deps_dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "C harness failed:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
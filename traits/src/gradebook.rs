@@ -0,0 +1,387 @@
+// A gradebook over a `StudentRegistry`: students get scores (0 to 100) in named courses,
+// and the gradebook computes per-course statistics, rankings and per-student transcripts.
+//
+// Rankings use "1224" competition ranking: students with the same score share a rank and
+// the next rank skips accordingly. Tied students are listed in the order of the derived
+// `Ord` on `Student` (name, then age) and finally by id, so the order never depends on
+// insertion order or hashing.
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::students::{StudentError, StudentId, StudentRegistry};
+use crate::Student;
+
+pub const MAX_SCORE: f64 = 100.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradeError {
+    UnknownStudent(StudentId),
+    UnknownCourse(String),
+    // Not a number between 0 and `MAX_SCORE`.
+    InvalidScore(f64),
+    Student(StudentError),
+}
+
+impl fmt::Display for GradeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GradeError::UnknownStudent(id) => write!(f, "no student with id {}", id),
+            GradeError::UnknownCourse(course) => write!(f, "no scores for course `{}`", course),
+            GradeError::InvalidScore(score) => {
+                write!(f, "score {} is not between 0 and {}", score, MAX_SCORE)
+            }
+            GradeError::Student(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for GradeError {}
+
+impl From<StudentError> for GradeError {
+    fn from(err: StudentError) -> GradeError {
+        GradeError::Student(err)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseStats {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    // Population standard deviation: every student of the course is counted.
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl CourseStats {
+    // Statistics of a non-empty list of scores.
+    fn of(scores: &[f64]) -> Option<CourseStats> {
+        if scores.is_empty() {
+            return None;
+        }
+        let mut sorted = scores.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let count = sorted.len();
+        let n = count as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let median = if count % 2 == 1 {
+            sorted[count / 2]
+        } else {
+            (sorted[count / 2 - 1] + sorted[count / 2]) / 2.0
+        };
+        let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+        Some(CourseStats {
+            count,
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            min: sorted[0],
+            max: sorted[count - 1],
+        })
+    }
+}
+
+// One line of a course ranking.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranked {
+    // 1-based; equal scores share a rank.
+    pub rank: usize,
+    pub id: StudentId,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptEntry {
+    pub course: String,
+    pub score: f64,
+    pub rank: usize,
+    // Number of students ranked in the course.
+    pub out_of: usize,
+    pub percentile_rank: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub id: StudentId,
+    pub student: Student,
+    // In course name order.
+    pub entries: Vec<TranscriptEntry>,
+    // Mean of the student's scores, `None` without any.
+    pub average: Option<f64>,
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Transcript for {} (id {}, age {})",
+            self.student.name, self.id, self.student.age
+        )?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "  {:<16} {:>6.1}  rank {}/{}  percentile {:.1}",
+                entry.course, entry.score, entry.rank, entry.out_of, entry.percentile_rank
+            )?;
+        }
+        match self.average {
+            Some(average) => writeln!(f, "  average {:.1}", average),
+            None => writeln!(f, "  no scores"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Gradebook {
+    students: StudentRegistry,
+    // course -> student -> score
+    courses: BTreeMap<String, BTreeMap<StudentId, f64>>,
+}
+
+impl Gradebook {
+    pub fn new() -> Gradebook {
+        Gradebook::default()
+    }
+
+    pub fn students(&self) -> &StudentRegistry {
+        &self.students
+    }
+
+    pub fn add_student(&mut self, student: Student) -> Result<StudentId, GradeError> {
+        Ok(self.students.add(student)?)
+    }
+
+    // Removes the student together with all their scores.
+    pub fn remove_student(&mut self, id: StudentId) -> Option<Student> {
+        let student = self.students.remove(id)?;
+        for scores in self.courses.values_mut() {
+            scores.remove(&id);
+        }
+        self.courses.retain(|_, scores| !scores.is_empty());
+        Some(student)
+    }
+
+    // Course names in alphabetical order.
+    pub fn courses(&self) -> impl Iterator<Item = &str> {
+        self.courses.keys().map(String::as_str)
+    }
+
+    // Records the student's score in the course, returning the score it replaces.
+    pub fn record_score(
+        &mut self,
+        id: StudentId,
+        course: &str,
+        score: f64,
+    ) -> Result<Option<f64>, GradeError> {
+        if self.students.get(id).is_none() {
+            return Err(GradeError::UnknownStudent(id));
+        }
+        if !(0.0..=MAX_SCORE).contains(&score) {
+            return Err(GradeError::InvalidScore(score));
+        }
+        Ok(self
+            .courses
+            .entry(course.to_owned())
+            .or_default()
+            .insert(id, score))
+    }
+
+    pub fn score(&self, id: StudentId, course: &str) -> Option<f64> {
+        self.courses.get(course)?.get(&id).copied()
+    }
+
+    fn course_scores(&self, course: &str) -> Result<&BTreeMap<StudentId, f64>, GradeError> {
+        self.courses
+            .get(course)
+            .ok_or_else(|| GradeError::UnknownCourse(course.to_owned()))
+    }
+
+    pub fn course_stats(&self, course: &str) -> Result<CourseStats, GradeError> {
+        let scores: Vec<f64> = self.course_scores(course)?.values().copied().collect();
+        CourseStats::of(&scores).ok_or_else(|| GradeError::UnknownCourse(course.to_owned()))
+    }
+
+    // Percentage of the course's scores below the student's, counting equal scores as
+    // half below, so the median student sits at 50.
+    pub fn percentile_rank(&self, id: StudentId, course: &str) -> Result<f64, GradeError> {
+        let scores = self.course_scores(course)?;
+        let score = *scores.get(&id).ok_or(GradeError::UnknownStudent(id))?;
+        let below = scores.values().filter(|&&other| other < score).count();
+        let equal = scores.values().filter(|&&other| other == score).count();
+        Ok((below as f64 + 0.5 * equal as f64) / scores.len() as f64 * 100.0)
+    }
+
+    // The course's students from the highest score down.
+    pub fn ranking(&self, course: &str) -> Result<Vec<Ranked>, GradeError> {
+        let mut entries: Vec<(StudentId, f64, &Student)> = self
+            .course_scores(course)?
+            .iter()
+            .map(|(&id, &score)| (id, score, self.students.get(id).expect("scored ids exist")))
+            .collect();
+        entries.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| a.2.cmp(b.2))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let mut ranking: Vec<Ranked> = Vec::with_capacity(entries.len());
+        for (position, (id, score, _)) in entries.into_iter().enumerate() {
+            let rank = match ranking.last() {
+                Some(previous) if previous.score == score => previous.rank,
+                _ => position + 1,
+            };
+            ranking.push(Ranked { rank, id, score });
+        }
+        Ok(ranking)
+    }
+
+    pub fn transcript(&self, id: StudentId) -> Result<Transcript, GradeError> {
+        let student = self
+            .students
+            .get(id)
+            .ok_or(GradeError::UnknownStudent(id))?
+            .clone();
+        let mut entries = Vec::new();
+        for (course, scores) in &self.courses {
+            let Some(&score) = scores.get(&id) else {
+                continue;
+            };
+            let ranking = self.ranking(course)?;
+            let rank = ranking
+                .iter()
+                .find(|ranked| ranked.id == id)
+                .map(|ranked| ranked.rank)
+                .expect("scored students are ranked");
+            entries.push(TranscriptEntry {
+                course: course.clone(),
+                score,
+                rank,
+                out_of: ranking.len(),
+                percentile_rank: self.percentile_rank(id, course)?,
+            });
+        }
+        let average = CourseStats::of(&entries.iter().map(|e| e.score).collect::<Vec<_>>())
+            .map(|stats| stats.mean);
+        Ok(Transcript {
+            id,
+            student,
+            entries,
+            average,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    // Five students with math scores 90, 75, 75, 60, 100 and two physics scores.
+    fn gradebook() -> (Gradebook, Vec<StudentId>) {
+        let mut book = Gradebook::new();
+        let mut ids = Vec::new();
+        for (name, age, math) in [
+            ("Dana", 20, 90.0),
+            ("Bea", 21, 75.0),
+            ("Abe", 22, 75.0),
+            ("Cal", 19, 60.0),
+            ("Eli", 20, 100.0),
+        ] {
+            let id = book
+                .add_student(Student {
+                    name: name.to_owned(),
+                    age,
+                })
+                .unwrap();
+            book.record_score(id, "math", math).unwrap();
+            ids.push(id);
+        }
+        book.record_score(ids[0], "physics", 80.0).unwrap();
+        book.record_score(ids[3], "physics", 70.0).unwrap();
+        (book, ids)
+    }
+
+    #[test]
+    fn computes_course_statistics() {
+        let (book, _) = gradebook();
+        let stats = book.course_stats("math").unwrap();
+
+        assert_eq!(stats.count, 5);
+        assert!(approx_eq(stats.mean, 80.0));
+        assert_eq!(stats.median, 75.0);
+        // Squared deviations 100 + 25 + 25 + 400 + 400 = 950, over 5 students.
+        assert!(approx_eq(stats.std_dev, 190f64.sqrt()));
+        assert_eq!((stats.min, stats.max), (60.0, 100.0));
+        assert_eq!(book.course_stats("physics").unwrap().median, 75.0);
+        assert_eq!(
+            book.course_stats("art"),
+            Err(GradeError::UnknownCourse("art".to_owned()))
+        );
+    }
+
+    #[test]
+    fn ties_share_a_rank_in_a_stable_order() {
+        let (book, ids) = gradebook();
+        let ranking = book.ranking("math").unwrap();
+        let ranks: Vec<(usize, StudentId)> = ranking.iter().map(|r| (r.rank, r.id)).collect();
+
+        // Abe and Bea tie for third and are listed by name.
+        assert_eq!(
+            ranks,
+            vec![
+                (1, ids[4]),
+                (2, ids[0]),
+                (3, ids[2]),
+                (3, ids[1]),
+                (5, ids[3])
+            ]
+        );
+        assert_eq!(book.percentile_rank(ids[1], "math"), Ok(40.0));
+        assert_eq!(book.percentile_rank(ids[4], "math"), Ok(90.0));
+    }
+
+    #[test]
+    fn transcripts_list_every_course_of_a_student() {
+        let (book, ids) = gradebook();
+        let transcript = book.transcript(ids[3]).unwrap();
+
+        assert_eq!(transcript.entries.len(), 2);
+        assert_eq!(transcript.entries[0].rank, 5);
+        assert_eq!(transcript.entries[1].course, "physics");
+        assert_eq!(transcript.entries[1].rank, 2);
+        assert_eq!(transcript.average, Some(65.0));
+        assert_eq!(
+            transcript.to_string(),
+            "Transcript for Cal (id 3, age 19)\n  \
+             math               60.0  rank 5/5  percentile 10.0\n  \
+             physics            70.0  rank 2/2  percentile 25.0\n  \
+             average 65.0\n"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_scores_and_forgets_removed_students() {
+        let (mut book, ids) = gradebook();
+
+        assert_eq!(
+            book.record_score(ids[0], "math", 101.0),
+            Err(GradeError::InvalidScore(101.0))
+        );
+        assert!(book.record_score(ids[0], "math", f64::NAN).is_err());
+        assert_eq!(book.record_score(ids[0], "math", 95.0), Ok(Some(90.0)));
+
+        book.remove_student(ids[0]);
+        book.remove_student(ids[3]);
+        assert_eq!(book.courses().collect::<Vec<_>>(), vec!["math"]);
+        assert_eq!(
+            book.record_score(ids[0], "math", 50.0),
+            Err(GradeError::UnknownStudent(ids[0]))
+        );
+        assert_eq!(book.ranking("math").unwrap().len(), 3);
+    }
+}
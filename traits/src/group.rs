@@ -0,0 +1,375 @@
+// Composite shapes. A `Group` holds child shapes, each with its own `Transform`, and is a
+// `Shape` itself, so groups can be nested to build a scene graph. Since the children are
+// `Box<dyn Shape>` trait objects, any mix of shape types (including other groups) can be
+// grouped together.
+use std::ops::Range;
+
+use serde_json::{json, Map, Value};
+
+use crate::bounds::BoundingBox;
+use crate::canvas::EMPTY_CELL;
+use crate::error::{self, ShapeError};
+use crate::json::{shape_to_json, vertex_to_json};
+use crate::shapes::Vertex;
+use crate::visitor::ShapeVisitor;
+use crate::{Canvas, Draw, DrawMode, OtherTrait, Shape, SvgStyle, ToJson, ToSvg};
+
+// Places a child inside its group: the child is first scaled (uniformly, around its
+// top-left corner), then rotated clockwise by `rotation` radians and finally translated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vertex,
+    pub rotation: f64,
+    pub scale: f64,
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform {
+            translation: (0.0, 0.0),
+            rotation: 0.0,
+            scale: 1.0,
+        }
+    }
+
+    pub fn translate(x: f64, y: f64) -> Transform {
+        Transform {
+            translation: (x, y),
+            ..Transform::identity()
+        }
+    }
+
+    pub fn with_rotation(self, rotation: f64) -> Transform {
+        Transform { rotation, ..self }
+    }
+
+    // Rejects scales that `invert` can't divide by: zero, negative, NaN and infinite ones.
+    pub fn with_scale(self, scale: f64) -> Result<Transform, ShapeError> {
+        Ok(Transform {
+            scale: error::check_scale(scale)?,
+            ..self
+        })
+    }
+
+    // Maps a point from the child's coordinates into the group's coordinates.
+    pub fn apply(&self, (x, y): Vertex) -> Vertex {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (x, y) = (x * self.scale, y * self.scale);
+        (
+            x * cos - y * sin + self.translation.0,
+            x * sin + y * cos + self.translation.1,
+        )
+    }
+
+    // Maps a point from the group's coordinates back into the child's coordinates.
+    pub fn invert(&self, (x, y): Vertex) -> Vertex {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (x, y) = (x - self.translation.0, y - self.translation.1);
+        (
+            (x * cos + y * sin) / self.scale,
+            (-x * sin + y * cos) / self.scale,
+        )
+    }
+
+    // Bounding box of a transformed box. Rotated boxes get the box around all 4 corners.
+    pub fn apply_box(&self, bbox: &BoundingBox) -> BoundingBox {
+        let corners = bbox.corners().map(|corner| self.apply(corner));
+        BoundingBox::from_points(&corners).unwrap_or(*bbox)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::identity()
+    }
+}
+
+// Group struct and its associated funcs
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Group {
+    children: Vec<(Box<dyn Shape>, Transform)>,
+}
+
+impl Group {
+    pub fn new() -> Group {
+        Group {
+            children: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, shape: Box<dyn Shape>, transform: Transform) -> &mut Self {
+        self.children.push((shape, transform));
+        self
+    }
+
+    pub fn with(mut self, shape: Box<dyn Shape>, transform: Transform) -> Group {
+        self.add(shape, transform);
+        self
+    }
+
+    pub fn children(&self) -> &[(Box<dyn Shape>, Transform)] {
+        &self.children
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    // Bounding box of all the children in the group's own coordinates. Nested groups are
+    // measured recursively through their own bounding boxes. An empty group has an empty
+    // box at (0, 0).
+    pub fn extent(&self) -> BoundingBox {
+        self.children
+            .iter()
+            .map(|(child, transform)| transform.apply_box(&child.bounding_box()))
+            .reduce(|a, b| a.union(&b))
+            .unwrap_or_else(|| BoundingBox::from_size(0.0, 0.0))
+    }
+}
+
+// Overlapping children are counted once per child, i.e. area is the sum of the areas.
+impl Shape for Group {
+    fn area(&self) -> f64 {
+        self.children
+            .iter()
+            .map(|(child, transform)| child.area() * transform.scale * transform.scale)
+            .sum()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.children
+            .iter()
+            .map(|(child, transform)| child.perimeter() * transform.scale)
+            .sum()
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_group(self);
+    }
+}
+
+// Whether the child, drawn on its own with its top-left corner at (0, 0), inks the cell
+// containing the point. The child is drawn into a 1 x 1 canvas showing only that cell,
+// which costs next to nothing because drawing is clipped to the canvas, and works for any
+// `Draw` implementation, including nested groups.
+fn inks(child: &dyn Shape, mode: DrawMode, (x, y): (f64, f64)) -> bool {
+    let (width, height) = child.bounding_box().size();
+    let (column, row) = (x.floor(), y.floor());
+    if column < 0.0 || row < 0.0 || column >= width.ceil() || row >= height.ceil() {
+        return false;
+    }
+    let (Ok(column), Ok(row)) = (i32::try_from(column as i64), i32::try_from(row as i64)) else {
+        return false;
+    };
+    let mut probe = Canvas::new(1, 1);
+    probe.set_mode(mode);
+    probe.set_origin(-column, -row);
+    child.draw_object(&mut probe);
+    probe.cell(0, 0).is_some_and(|cell| cell != EMPTY_CELL)
+}
+
+// The cells from `min` to `max` along one axis that are also in `visible`.
+fn clamped(min: f64, max: f64, visible: Range<i64>) -> Range<i64> {
+    (min.floor() as i64).max(visible.start)..(max.ceil() as i64).min(visible.end)
+}
+
+// Every cell covered by a transformed child and visible on the canvas is mapped back into
+// the child to see whether it is inked, so the work is bounded by the canvas rather than
+// by the size of the children.
+impl Draw for Group {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        let extent = self.extent();
+        let (offset_x, offset_y) = (extent.min.0.floor() as i64, extent.min.1.floor() as i64);
+        // The group cells that land on the canvas.
+        let (origin_x, origin_y) = canvas.origin();
+        let left = offset_x - i64::from(origin_x);
+        let top = offset_y - i64::from(origin_y);
+        let size = |cells: usize| i64::try_from(cells).unwrap_or(i64::MAX);
+        let columns = left..left.saturating_add(size(canvas.width()));
+        let rows = top..top.saturating_add(size(canvas.height()));
+        for (child, transform) in &self.children {
+            // The fields are public, so the scale may not have gone through `with_scale`.
+            if error::check_scale(transform.scale).is_err() {
+                continue;
+            }
+            let target = transform.apply_box(&child.bounding_box());
+            for y in clamped(target.min.1, target.max.1, rows.clone()) {
+                for x in clamped(target.min.0, target.max.0, columns.clone()) {
+                    let local = transform.invert((x as f64 + 0.5, y as f64 + 0.5));
+                    if !inks(child.as_ref(), canvas.mode(), local) {
+                        continue;
+                    }
+                    canvas.plot_absolute(x - left, y - top);
+                }
+            }
+        }
+    }
+}
+
+impl OtherTrait for Group {}
+
+impl ToJson for Group {
+    fn json_kind(&self) -> &'static str {
+        "group"
+    }
+
+    fn json_fields(&self) -> Map<String, Value> {
+        let children: Vec<Value> = self
+            .children
+            .iter()
+            .map(|(child, transform)| {
+                json!({
+                    "shape": shape_to_json(child.as_ref()),
+                    "translation": vertex_to_json(transform.translation),
+                    "rotation": transform.rotation,
+                    "scale": transform.scale,
+                })
+            })
+            .collect();
+        let mut fields = Map::new();
+        fields.insert("children".to_owned(), children.into());
+        fields
+    }
+}
+
+impl ToSvg for Group {
+    fn svg_size(&self) -> (f64, f64) {
+        self.extent().size()
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        let extent = self.extent();
+        let mut element = format!(
+            r#"<g transform="translate({},{})">"#,
+            x - extent.min.0,
+            y - extent.min.1
+        );
+        for (child, transform) in &self.children {
+            element.push_str(&format!(
+                r#"<g transform="translate({},{}) rotate({}) scale({})">{}</g>"#,
+                transform.translation.0,
+                transform.translation.1,
+                transform.rotation.to_degrees(),
+                transform.scale,
+                child.svg_element(0.0, 0.0, style)
+            ));
+        }
+        element.push_str("</g>");
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::{DrawMode, Rectangle, Square};
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn transform_round_trips() {
+        let transform = Transform::translate(3.0, -2.0)
+            .with_rotation(0.7)
+            .with_scale(2.5)
+            .unwrap();
+        let (x, y) = transform.invert(transform.apply((1.5, 4.0)));
+
+        assert!(approx_eq(x, 1.5) && approx_eq(y, 4.0));
+    }
+
+    #[test]
+    fn aggregates_area_and_perimeter() {
+        let group = Group::new()
+            .with(Box::new(Square::new(2)), Transform::identity())
+            .with(
+                Box::new(Rectangle::new(3, 1)),
+                Transform::translate(5.0, 0.0).with_scale(2.0).unwrap(),
+            );
+
+        assert!(approx_eq(group.area(), 4.0 + 3.0 * 4.0));
+        assert!(approx_eq(group.perimeter(), 8.0 + 8.0 * 2.0));
+    }
+
+    #[test]
+    fn nested_groups_compute_bounding_boxes_recursively() {
+        let inner = Group::new()
+            .with(Box::new(Rectangle::new(4, 2)), Transform::identity())
+            .with(Box::new(Square::new(1)), Transform::translate(-1.0, 5.0));
+        let inner_extent = inner.extent();
+        let outer = Group::new().with(
+            Box::new(inner),
+            Transform::translate(10.0, 0.0).with_rotation(FRAC_PI_2),
+        );
+        let extent = outer.extent();
+
+        assert_eq!(inner_extent, BoundingBox::new((-1.0, 0.0), (4.0, 6.0)));
+        // The inner group's 5 x 6 box, rotated a quarter turn clockwise around the
+        // translation point, becomes 6 wide and 5 tall to the left of x = 10.
+        assert!(approx_eq(extent.min.0, 4.0) && approx_eq(extent.max.0, 10.0));
+        assert!(approx_eq(extent.min.1, 0.0) && approx_eq(extent.max.1, 5.0));
+    }
+
+    #[test]
+    fn draws_transformed_children() {
+        let group = Group::new()
+            .with(Box::new(Rectangle::new(3, 1)), Transform::identity())
+            .with(
+                Box::new(Rectangle::new(3, 1)),
+                Transform::translate(5.0, 0.0).with_rotation(FRAC_PI_2),
+            );
+        let mut canvas = Canvas::new(5, 3);
+        canvas.place(&group, 0, 0, DrawMode::Filled);
+        let rows: Vec<String> = canvas.render().lines().map(String::from).collect();
+
+        assert_eq!(rows, vec!["###.#", "....#", "....#"]);
+    }
+
+    #[test]
+    fn huge_children_only_visit_the_canvas() {
+        let huge = Group::new().with(
+            Box::new(Rectangle::new(100_000, 100_000)),
+            Transform::identity(),
+        );
+        // Shrunk down to a single cell.
+        let tiny = Group::new().with(
+            Box::new(Square::new(100_000)),
+            Transform::identity().with_scale(1e-5).unwrap(),
+        );
+        let mut canvas = Canvas::new(4, 4);
+        canvas.place(&huge, 1, 2, DrawMode::Outline);
+        canvas.place(&tiny, 2, 3, DrawMode::Filled);
+        let rows: Vec<String> = canvas.render().lines().map(String::from).collect();
+
+        assert_eq!(rows, vec!["....", "....", ".###", ".##."]);
+    }
+
+    #[test]
+    fn rejects_scales_that_cannot_be_inverted() {
+        for scale in [0.0, -2.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                Transform::identity().with_scale(scale),
+                Err(ShapeError::InvalidScale(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn exports_nested_svg_groups() {
+        let group = Group::new().with(
+            Box::new(Square::new(2)),
+            Transform::translate(1.0, 1.0).with_scale(2.0).unwrap(),
+        );
+        let element = group.svg_element(0.0, 0.0, &SvgStyle::default());
+
+        assert!(element.starts_with(r#"<g transform="translate(-1,-1)">"#));
+        assert!(element.contains(r#"<g transform="translate(1,1) rotate(0) scale(2)"><rect"#));
+    }
+}
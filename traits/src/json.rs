@@ -0,0 +1,349 @@
+// JSON persistence for shape trait objects. Every `Shape` also implements `ToJson` (one of
+// its supertraits), which gives the `"kind"` discriminator and the fields of the concrete
+// type:
+//
+//     [{"kind": "square", "side": 4}, {"kind": "rectangle", "length": 8, "breadth": 6}]
+//
+// Going back from JSON to `Box<dyn Shape>` needs to know which type belongs to which
+// kind, so `JsonDecoders` maps each kind to a decoding closure. Crates with their own
+// shapes register a decoder for their kind next to the built-in ones.
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+use crate::shapes::Vertex;
+use crate::{
+    Circle, Group, Polygon, Rectangle, RegularPolygon, Shape, ShapeError, Square, Transform,
+    Triangle,
+};
+
+pub trait ToJson {
+    // The value of the `"kind"` field.
+    fn json_kind(&self) -> &'static str;
+
+    // All the other fields of the object.
+    fn json_fields(&self) -> Map<String, Value>;
+}
+
+#[derive(Debug)]
+pub enum JsonError {
+    Syntax(serde_json::Error),
+    // A shape was not a JSON object with a string `"kind"` field.
+    MissingKind,
+    UnknownKind(String),
+    MissingField { kind: String, field: &'static str },
+    InvalidField { kind: String, field: &'static str },
+    Shape(ShapeError),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::Syntax(err) => write!(f, "invalid JSON: {}", err),
+            JsonError::MissingKind => write!(f, "shape object without a \"kind\" field"),
+            JsonError::UnknownKind(kind) => write!(f, "unknown shape kind `{}`", kind),
+            JsonError::MissingField { kind, field } => {
+                write!(f, "{} is missing the `{}` field", kind, field)
+            }
+            JsonError::InvalidField { kind, field } => {
+                write!(f, "{} has an invalid `{}` field", kind, field)
+            }
+            JsonError::Shape(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for JsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            JsonError::Syntax(err) => Some(err),
+            JsonError::Shape(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for JsonError {
+    fn from(err: serde_json::Error) -> JsonError {
+        JsonError::Syntax(err)
+    }
+}
+
+impl From<ShapeError> for JsonError {
+    fn from(err: ShapeError) -> JsonError {
+        JsonError::Shape(err)
+    }
+}
+
+// The fields of one shape object, with helpers that turn missing or mistyped fields into
+// `JsonError`s naming the shape kind.
+pub struct Fields<'a> {
+    kind: &'a str,
+    map: &'a Map<String, Value>,
+}
+
+impl<'a> Fields<'a> {
+    pub fn kind(&self) -> &'a str {
+        self.kind
+    }
+
+    pub fn get(&self, field: &'static str) -> Result<&'a Value, JsonError> {
+        self.map.get(field).ok_or_else(|| JsonError::MissingField {
+            kind: self.kind.to_owned(),
+            field,
+        })
+    }
+
+    pub fn invalid(&self, field: &'static str) -> JsonError {
+        JsonError::InvalidField {
+            kind: self.kind.to_owned(),
+            field,
+        }
+    }
+
+    pub fn f64(&self, field: &'static str) -> Result<f64, JsonError> {
+        self.get(field)?.as_f64().ok_or_else(|| self.invalid(field))
+    }
+
+    pub fn i32(&self, field: &'static str) -> Result<i32, JsonError> {
+        self.get(field)?
+            .as_i64()
+            .and_then(|value| i32::try_from(value).ok())
+            .ok_or_else(|| self.invalid(field))
+    }
+
+    pub fn vertices(&self, field: &'static str) -> Result<Vec<Vertex>, JsonError> {
+        let list = self
+            .get(field)?
+            .as_array()
+            .ok_or_else(|| self.invalid(field))?;
+        list.iter()
+            .map(|point| vertex_from_json(point).ok_or_else(|| self.invalid(field)))
+            .collect()
+    }
+}
+
+pub fn vertex_to_json((x, y): Vertex) -> Value {
+    json!([x, y])
+}
+
+fn vertex_from_json(value: &Value) -> Option<Vertex> {
+    match value.as_array()?.as_slice() {
+        [x, y] => Some((x.as_f64()?, y.as_f64()?)),
+        _ => None,
+    }
+}
+
+pub type ShapeDecoder = Box<dyn Fn(&Fields, &JsonDecoders) -> Result<Box<dyn Shape>, JsonError>>;
+
+pub struct JsonDecoders {
+    decoders: HashMap<String, ShapeDecoder>,
+}
+
+impl JsonDecoders {
+    pub fn new() -> JsonDecoders {
+        JsonDecoders {
+            decoders: HashMap::new(),
+        }
+    }
+
+    // Decoders for every shape in this crate.
+    pub fn with_builtin_shapes() -> JsonDecoders {
+        let mut decoders = JsonDecoders::new();
+        decoders.register("square", |fields, _| {
+            Ok(Box::new(Square::try_new(fields.i32("side")?)?))
+        });
+        decoders.register("rectangle", |fields, _| {
+            Ok(Box::new(Rectangle::try_new(
+                fields.i32("length")?,
+                fields.i32("breadth")?,
+            )?))
+        });
+        decoders.register("circle", |fields, _| {
+            Ok(Box::new(Circle::try_new(fields.f64("radius")?)?))
+        });
+        decoders.register("triangle", |fields, _| {
+            match fields.vertices("vertices")?.as_slice() {
+                &[a, b, c] => Ok(Box::new(Triangle::try_new(a, b, c)?)),
+                _ => Err(fields.invalid("vertices")),
+            }
+        });
+        decoders.register("regular_polygon", |fields, _| {
+            let sides = fields.i32("sides")?;
+            let sides = u32::try_from(sides).map_err(|_| fields.invalid("sides"))?;
+            Ok(Box::new(RegularPolygon::try_new(
+                sides,
+                fields.f64("side_length")?,
+            )?))
+        });
+        decoders.register("polygon", |fields, _| {
+            Ok(Box::new(Polygon::try_new(fields.vertices("vertices")?)?))
+        });
+        decoders.register("group", |fields, decoders| {
+            let children = fields
+                .get("children")?
+                .as_array()
+                .ok_or_else(|| fields.invalid("children"))?;
+            let mut group = Group::new();
+            for child in children {
+                let child_fields = Fields {
+                    kind: "group child",
+                    map: child
+                        .as_object()
+                        .ok_or_else(|| fields.invalid("children"))?,
+                };
+                let (x, y) = vertex_from_json(child_fields.get("translation")?)
+                    .ok_or_else(|| child_fields.invalid("translation"))?;
+                let transform = Transform::translate(x, y)
+                    .with_rotation(child_fields.f64("rotation")?)
+                    .with_scale(child_fields.f64("scale")?)?;
+                group.add(decoders.decode(child_fields.get("shape")?)?, transform);
+            }
+            Ok(Box::new(group))
+        });
+        decoders
+    }
+
+    // Registers the decoder for `kind`, replacing any previous one.
+    pub fn register<F>(&mut self, kind: &str, decoder: F)
+    where
+        F: Fn(&Fields, &JsonDecoders) -> Result<Box<dyn Shape>, JsonError> + 'static,
+    {
+        self.decoders.insert(kind.to_owned(), Box::new(decoder));
+    }
+
+    pub fn decode(&self, value: &Value) -> Result<Box<dyn Shape>, JsonError> {
+        let map = value.as_object().ok_or(JsonError::MissingKind)?;
+        let kind = map
+            .get("kind")
+            .and_then(Value::as_str)
+            .ok_or(JsonError::MissingKind)?;
+        let decoder = self
+            .decoders
+            .get(kind)
+            .ok_or_else(|| JsonError::UnknownKind(kind.to_owned()))?;
+        decoder(&Fields { kind, map }, self)
+    }
+
+    // Reads a JSON array of shape objects.
+    pub fn shapes_from_json(&self, json: &str) -> Result<Vec<Box<dyn Shape>>, JsonError> {
+        let values: Vec<Value> = serde_json::from_str(json)?;
+        values.iter().map(|value| self.decode(value)).collect()
+    }
+}
+
+impl Default for JsonDecoders {
+    fn default() -> JsonDecoders {
+        JsonDecoders::new()
+    }
+}
+
+// The shape as a JSON object: its `"kind"` plus its fields.
+pub fn shape_to_json(shape: &dyn Shape) -> Value {
+    let mut object = shape.json_fields();
+    object.insert("kind".to_owned(), Value::from(shape.json_kind()));
+    Value::Object(object)
+}
+
+pub fn shapes_to_json(shapes: &[Box<dyn Shape>]) -> String {
+    let values: Vec<Value> = shapes
+        .iter()
+        .map(|shape| shape_to_json(shape.as_ref()))
+        .collect();
+    serde_json::to_string_pretty(&values).expect("JSON values always serialize")
+}
+
+// Reads shapes of the kinds built into this crate.
+pub fn shapes_from_json(json: &str) -> Result<Vec<Box<dyn Shape>>, JsonError> {
+    JsonDecoders::with_builtin_shapes().shapes_from_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(shapes: &[Box<dyn Shape>]) -> Vec<Box<dyn Shape>> {
+        shapes_from_json(&shapes_to_json(shapes)).unwrap()
+    }
+
+    #[test]
+    fn mixed_shapes_round_trip() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Square::new(4)),
+            Box::new(Rectangle::new(8, 6)),
+            Box::new(Circle::new(1.5)),
+            Box::new(Triangle::new((0.0, 0.0), (4.0, 0.0), (0.0, 3.0))),
+            Box::new(RegularPolygon::new(6, 2.0)),
+            Box::new(Polygon::new(vec![(0.0, 0.0), (2.0, 0.0), (1.0, 5.0)])),
+            Box::new(Group::new().with(
+                Box::new(Square::new(2)),
+                Transform::translate(1.0, 2.0).with_scale(3.0).unwrap(),
+            )),
+        ];
+        let restored = round_trip(&shapes);
+
+        assert_eq!(restored.len(), shapes.len());
+        for (before, after) in shapes.iter().zip(&restored) {
+            assert_eq!(
+                shape_to_json(before.as_ref()),
+                shape_to_json(after.as_ref())
+            );
+            assert_eq!(before.area(), after.area());
+        }
+    }
+
+    #[test]
+    fn writes_kind_and_fields() {
+        assert_eq!(
+            shape_to_json(&Rectangle::new(8, 6)),
+            json!({"kind": "rectangle", "length": 8, "breadth": 6})
+        );
+    }
+
+    #[test]
+    fn reports_unknown_kinds_and_bad_fields() {
+        let err = shapes_from_json(r#"[{"kind": "hexagon", "side": 1}]"#)
+            .err()
+            .unwrap();
+        assert!(matches!(&err, JsonError::UnknownKind(kind) if kind == "hexagon"));
+        assert_eq!(err.to_string(), "unknown shape kind `hexagon`");
+
+        assert!(matches!(
+            shapes_from_json(r#"[{"kind": "square"}]"#),
+            Err(JsonError::MissingField { field: "side", .. })
+        ));
+        assert!(matches!(
+            shapes_from_json(r#"[{"kind": "square", "side": 1.5}]"#),
+            Err(JsonError::InvalidField { field: "side", .. })
+        ));
+        assert!(matches!(
+            shapes_from_json(r#"[{"kind": "square", "side": -1}]"#),
+            Err(JsonError::Shape(_))
+        ));
+        assert!(matches!(
+            shapes_from_json(r#"[{"side": 1}]"#),
+            Err(JsonError::MissingKind)
+        ));
+        assert!(matches!(shapes_from_json("[{"), Err(JsonError::Syntax(_))));
+
+        let flat_child = r#"[{"kind": "group", "children": [{"shape": {"kind": "square",
+            "side": 1}, "translation": [0, 0], "rotation": 0, "scale": 0}]}]"#;
+        assert!(matches!(
+            shapes_from_json(flat_child),
+            Err(JsonError::Shape(ShapeError::InvalidScale(_)))
+        ));
+    }
+
+    #[test]
+    fn custom_decoders_can_be_registered() {
+        let mut decoders = JsonDecoders::new();
+        decoders.register("unit_square", |_, _| Ok(Box::new(Square::new(1))));
+        let shapes = decoders
+            .shapes_from_json(r#"[{"kind": "unit_square"}]"#)
+            .unwrap();
+
+        assert_eq!(shapes[0].area(), 1.0);
+    }
+}
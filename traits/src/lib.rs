@@ -0,0 +1,442 @@
+// Traits are like interfaces in Java where we define a common function signature for
+// different Types like structs, possibly enums too. This as of my current understanding
+// helps us to maintain a standard of development
+
+// Character-grid canvas that the `Draw` trait rasterizes shapes into
+pub mod canvas;
+
+// SVG export backend, with the `ToSvg` hook every shape implements
+pub mod svg;
+
+// Fallible parser for textual shape descriptions and scene files
+pub mod parser;
+
+// Circles, triangles and polygons with floating-point geometry
+pub mod shapes;
+
+// Axis-aligned bounding boxes
+pub mod bounds;
+
+// Composite shapes with per-child transforms (a simple scene graph)
+pub mod group;
+
+// Typed errors for invalid dimensions and overflowing measurements
+pub mod error;
+
+// Runtime registry of named shape constructors
+pub mod registry;
+
+// Enum dispatch alternative to `Box<dyn Shape>`
+pub mod any_shape;
+
+// Intersection and containment queries between positioned shapes
+pub mod collision;
+
+// JSON persistence for shape trait objects, with the `ToJson` hook every shape implements
+pub mod json;
+
+// Clone, Debug and equality for `Box<dyn Shape>` trait objects
+pub mod dyn_shape;
+
+// Capability markers (convex, regular, axis-aligned) with compile-time fast paths
+pub mod capabilities;
+
+// The `shapes` command line calculator
+pub mod cli;
+
+// C ABI for area and perimeter of squares and rectangles
+pub mod ffi;
+
+// Runtime loading of shape plugins from shared libraries
+pub mod plugin;
+
+// Visitor API for exporters and analyzers over all shape types
+pub mod visitor;
+
+// Quadtree spatial index for fast hit-testing of many shapes
+pub mod quadtree;
+
+// Bin packing of rectangles into a container
+pub mod packing;
+
+// Student records store with lookups and CSV import/export
+pub mod students;
+
+// Courses, scores, statistics and transcripts for the student records
+pub mod gradebook;
+
+pub use any_shape::AnyShape;
+pub use bounds::BoundingBox;
+pub use canvas::{Canvas, DrawMode};
+pub use capabilities::{AxisAligned, Convex, Regular};
+pub use collision::{contains_point, contains_shape, intersects, Outline, Placed};
+pub use dyn_shape::{downcast_ref, DynShape};
+pub use error::ShapeError;
+pub use gradebook::{CourseStats, GradeError, Gradebook, Transcript};
+pub use group::{Group, Transform};
+pub use json::{shapes_from_json, shapes_to_json, JsonDecoders, JsonError, ToJson};
+pub use packing::{Heuristic, Packer, Packing};
+pub use parser::{parse_scene, parse_shape, ShapeParseError};
+pub use plugin::{LoadedPlugin, PluginError};
+pub use quadtree::{QuadTree, ShapeId};
+pub use registry::{BuildError, ShapeRegistry};
+pub use shapes::{Circle, Polygon, RegularPolygon, Triangle};
+pub use students::{StudentId, StudentRegistry};
+pub use svg::{SvgStyle, SvgWriter, ToSvg};
+pub use visitor::{ShapeStats, ShapeVisitor, TextExporter};
+
+// Square struct and its associated funcs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Square {
+    side: i32,
+}
+
+impl Square {
+    pub fn new(side: i32) -> Square {
+        Square { side }
+    }
+
+    pub fn side(&self) -> i32 {
+        self.side
+    }
+
+    // Validated constructor, rejects negative sides.
+    pub fn try_new(side: i32) -> Result<Square, ShapeError> {
+        Ok(Square {
+            side: error::check_dimension("side", side)?,
+        })
+    }
+
+    // Exact integer measurements. `Shape::area` and `Shape::perimeter` compute in `f64`
+    // and can't overflow, but these report `ShapeError::Overflow` instead of wrapping
+    // when the result doesn't fit in an `i32`.
+    pub fn checked_area(&self) -> Result<i32, ShapeError> {
+        self.side.checked_mul(self.side).ok_or(ShapeError::Overflow)
+    }
+
+    pub fn checked_perimeter(&self) -> Result<i32, ShapeError> {
+        self.side.checked_mul(4).ok_or(ShapeError::Overflow)
+    }
+}
+
+// Rectangle struct and its associated funcs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rectangle {
+    length: i32,
+    breadth: i32,
+}
+
+impl Rectangle {
+    pub fn new(length: i32, breadth: i32) -> Rectangle {
+        Rectangle { length, breadth }
+    }
+
+    pub fn length(&self) -> i32 {
+        self.length
+    }
+
+    pub fn breadth(&self) -> i32 {
+        self.breadth
+    }
+
+    // Validated constructor, rejects negative sides.
+    pub fn try_new(length: i32, breadth: i32) -> Result<Rectangle, ShapeError> {
+        Ok(Rectangle {
+            length: error::check_dimension("length", length)?,
+            breadth: error::check_dimension("breadth", breadth)?,
+        })
+    }
+
+    pub fn checked_area(&self) -> Result<i32, ShapeError> {
+        self.length
+            .checked_mul(self.breadth)
+            .ok_or(ShapeError::Overflow)
+    }
+
+    pub fn checked_perimeter(&self) -> Result<i32, ShapeError> {
+        self.length
+            .checked_add(self.breadth)
+            .and_then(|sum| sum.checked_mul(2))
+            .ok_or(ShapeError::Overflow)
+    }
+}
+
+//Supertraits -> This is something like inheritance but not exactly it. Here the Draw
+// trait will be delegated to the Shape trait, such that whatever object/type implements
+// the Shape trait will also have to implement the Draw trait.
+// `draw_object` rasterizes the object into the canvas relative to the canvas' current
+// origin, using the canvas' current draw mode (filled or outline).
+pub trait Draw {
+    fn draw_object(&self, canvas: &mut Canvas);
+}
+
+// Empty traits such as this are known as `Marker Traits`. They are useful when we add
+// some supertraits to them such that we can apply the marker trait on a type to add some
+// functionality to them via the supertraits.
+// OtherTrait is no longer empty: it tags shapes with their capabilities at runtime. The
+// default methods say "no", and each shape overrides the ones that apply to it. Generic
+// code can bound on the matching compile-time markers in src/capabilities.rs instead.
+pub trait OtherTrait {
+    fn is_convex(&self) -> bool {
+        false
+    }
+
+    fn is_regular(&self) -> bool {
+        false
+    }
+
+    fn is_axis_aligned(&self) -> bool {
+        false
+    }
+}
+
+// Common trait/interface for the Square and Rectangle struct. Here the Draw,
+// OtherTrait, ToSvg and ToJson are super traits for the Shape trait. Multiple supertraits
+// can be added using the `+` symbol. All the traits that are using the base
+// trait must also have an implementation of the super trait. `DynShape` and `Debug` make
+// `Box<dyn Shape>` clonable, comparable and printable (see src/dyn_shape.rs).
+
+// Area and perimeter are `f64` so that shapes such as circles, whose measurements are
+// not whole numbers, can implement the same trait as squares and rectangles.
+
+// Square traits implementation
+pub trait Shape: Draw + OtherTrait + ToSvg + ToJson + DynShape + std::fmt::Debug {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+
+    // Default methods - implementors get this for free but can override it. The box is
+    // in the shape's local coordinates, where its top-left corner sits at (0, 0).
+    fn bounding_box(&self) -> BoundingBox {
+        let (width, height) = self.svg_size();
+        BoundingBox::from_size(width, height)
+    }
+
+    // The exact geometry used by the collision queries, in the same local coordinates.
+    // Defaults to the bounding box, which is exact for `Square` and `Rectangle`.
+    fn outline(&self) -> Outline {
+        Outline::Rect(self.bounding_box())
+    }
+
+    // Calls the `visit_*` method of the visitor for the shape's own type.
+    fn accept(&self, visitor: &mut dyn ShapeVisitor);
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        f64::from(self.side) * f64::from(self.side)
+    }
+
+    fn perimeter(&self) -> f64 {
+        4.0 * f64::from(self.side)
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_square(self);
+    }
+}
+
+impl Draw for Square {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        canvas.draw_rect(0, 0, self.side, self.side);
+    }
+}
+
+impl OtherTrait for Square {
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn is_regular(&self) -> bool {
+        true
+    }
+
+    fn is_axis_aligned(&self) -> bool {
+        true
+    }
+}
+
+impl Convex for Square {}
+
+impl Regular for Square {
+    fn circumradius(&self) -> f64 {
+        f64::from(self.side) / 2f64.sqrt()
+    }
+
+    fn inradius(&self) -> f64 {
+        f64::from(self.side) / 2.0
+    }
+}
+
+impl AxisAligned for Square {}
+
+impl ToJson for Square {
+    fn json_kind(&self) -> &'static str {
+        "square"
+    }
+
+    fn json_fields(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut fields = serde_json::Map::new();
+        fields.insert("side".to_owned(), self.side.into());
+        fields
+    }
+}
+
+impl ToSvg for Square {
+    fn svg_size(&self) -> (f64, f64) {
+        (f64::from(self.side), f64::from(self.side))
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+            x,
+            y,
+            self.side,
+            self.side,
+            style.attributes()
+        )
+    }
+}
+
+// Rectangle traits implementation
+
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        f64::from(self.length) * f64::from(self.breadth)
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (f64::from(self.length) + f64::from(self.breadth))
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_rectangle(self);
+    }
+}
+
+impl Draw for Rectangle {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        canvas.draw_rect(0, 0, self.length, self.breadth);
+    }
+}
+
+impl OtherTrait for Rectangle {
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn is_axis_aligned(&self) -> bool {
+        true
+    }
+}
+
+impl Convex for Rectangle {}
+
+impl AxisAligned for Rectangle {}
+
+impl ToJson for Rectangle {
+    fn json_kind(&self) -> &'static str {
+        "rectangle"
+    }
+
+    fn json_fields(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut fields = serde_json::Map::new();
+        fields.insert("length".to_owned(), self.length.into());
+        fields.insert("breadth".to_owned(), self.breadth.into());
+        fields
+    }
+}
+
+impl ToSvg for Rectangle {
+    fn svg_size(&self) -> (f64, f64) {
+        (f64::from(self.length), f64::from(self.breadth))
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+            x,
+            y,
+            self.length,
+            self.breadth,
+            style.attributes()
+        )
+    }
+}
+
+// Static Dispatch / Monomorphization
+// Behind the scenes, when we use static dispatch, Rust creates dedicated functions
+// for the types on which this function was called.This is done at compile time. For
+// example, we called this function using a `Sqaure` and a `Rectangle` type, thus rust
+// behind the scenes created separate versions of the below function for them. This
+// is static dispatch or Monomorphization.
+// *********NOTE********
+// The special advantage of static dispatch is that it improves performance by eliminating
+// runtime overhead. The `area` function below can also be categorized into static dispatch.
+pub fn share_properties<T: Shape>(obj: T) -> (f64, f64) {
+    (obj.area(), obj.perimeter())
+}
+
+// Dynamic Dispatch - similar to static dispatch, but here the specific implementations of
+// the function are not created at compile time but at runtime. Box is a smart pointer. It
+// is a pointer to some Heap allocated data. We are also not using the trait bound when using
+// dynamic dispatch. The `dyn` keyword stands for dynamic dispatch and is used to define a
+// trait object here. The essential requirement of a trait object is that it must be behind
+// a pointer. In this case, we use the Box smart pointer. Trait objects allows us to define
+// a type which implements a trait without knowing or having knowledge of what that type is
+// at the compile time. In this case, the specialized versions of the functions will be not
+// be generated and the resolution of the function will take place at runtime/execution time
+// and not at compile time.
+pub fn share_properties_dynamic(obj: Box<dyn Shape>) -> (f64, f64) {
+    (obj.area(), obj.perimeter())
+}
+
+// ********NOTE*************
+// Trait Object = Dynamic Dispatch
+// Trait Bound = Static Dispatch
+// A third option, enum dispatch, lives in `any_shape.rs`. The `dispatch_bench` binary
+// (src/bin/dispatch_bench.rs) times all three.
+
+// Function implemented with Trait Bound. This is useful in such scenarios where we
+// have a common method that we can call on the object passed to the function. But
+// Rust should have some way to know or limit the type of objects that are passed in
+// such that they should have associated to them the method we are calling on them.
+// Here, in this scenario Trait Bounds help us by limiting or defining the types that
+// can be passed to the function. The types are limited or differentiated from the rest
+// on the basis of Traits. Thus, in the below example, the type of object passed to the
+// function must implement the Shape trait.
+pub fn area<T: Shape>(obj: &T) -> f64 {
+    obj.area()
+}
+
+// Function using Dynamic Dispatch to return different objects of similar type using
+// `Box` smart pointer. We can return a trait bound by returning `impl Shape` if we
+// are only returning a single concrete type say `Square`. Dimensions that describe
+// neither shape give back `None`; for textual input with error positions see
+// `parser::parse_shape`, and for building shapes from names chosen at runtime see
+// `registry::ShapeRegistry`.
+pub fn returns_shape(dimension: Vec<i32>) -> Option<Box<dyn Shape>> {
+    match dimension[..] {
+        [side] => Some(Box::new(Square::new(side))),
+        [length, breadth] => Some(Box::new(Rectangle::new(length, breadth))),
+        _ => None,
+    }
+}
+
+// Derive Traits - traits that can be automatically implemented for a type using the
+// `derive` attribute. These traits are applied to structs and enums to provide default
+// implementation for certain behaviors. For example, in the below lines of code, derive
+// is implementing the `Debug`trait for the Student struct. Thus, we don't have to implement
+// the trait manually.
+// The derive traits are available for common behavior such as comparisons, cloning and
+// initializing instances of structs from some default value.
+// Debug trait - used to print a struct/enum via println.
+// PartialEq trait - used to compare two instances of the same type.
+// Eq and Ord trait - a total order, here by name and then by age (the order of the
+// fields), which lets us sort students. Hash trait - lets students be keys of a HashMap
+// or HashSet. `students::StudentRegistry` uses all of these.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Student {
+    pub name: String,
+    pub age: i32,
+}
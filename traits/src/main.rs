@@ -1,159 +1,10 @@
-// Traits are like interfaces in Java where we define a common function signature for
-// different Types like structs, possibly enums too. This as of my current understanding
-// helps us to maintain a standard of development
-
-// Square struct and its associated funcs
-struct Square {
-    side: i32,
-}
-
-impl Square {
-    fn new(side: i32) -> Square {
-        Square { side }
-    }
-}
-
-// Rectangle struct and its associated funcs
-struct Rectangle {
-    length: i32,
-    breadth: i32,
-}
-
-impl Rectangle {
-    fn new(length: i32, breadth: i32) -> Rectangle {
-        Rectangle { length, breadth }
-    }
-}
-
-//Supertraits -> This is something like inheritance but not exactly it. Here the Draw
-// trait will be delegated to the Shape trait, such that whatever object/type implements
-// the Shape trait will also have to implement the Draw trait.
-trait Draw {
-    fn draw_object(&self);
-}
-
-trait OtherTrait {} // Empty traits such as this are known as `Marker Traits`
-                    // They are useful when we add some supertraits to them such that we can apply
-                    // the marker trait on a type to add some functionality to them via the super-
-                    // traits.
-
-// Common trait/interface for the Square and Rectangle struct. Here the Draw,
-// and OtherTrait are super traits for the Shape trait. Multiple supertraits
-// can be added using the `+` symbol. All the traits that are using the base
-// trait must also have an implementation of the super trait.
-
-// Square traits implementation
-trait Shape: Draw + OtherTrait {
-    fn area(&self) -> i32;
-    fn perimeter(&self) -> i32;
-}
-
-impl Shape for Square {
-    fn area(&self) -> i32 {
-        self.side * self.side
-    }
-
-    fn perimeter(&self) -> i32 {
-        4 * self.side
-    }
-}
-
-impl Draw for Square {
-    fn draw_object(&self) {
-        println!("Drawing a Square object.");
-    }
-}
-
-impl OtherTrait for Square {}
-
-// Rectangle traits implementation
-
-impl Shape for Rectangle {
-    fn area(&self) -> i32 {
-        self.length * self.breadth
-    }
-
-    fn perimeter(&self) -> i32 {
-        2 * (self.length + self.breadth)
-    }
-}
-
-impl Draw for Rectangle {
-    fn draw_object(&self) {
-        println!("Drawing a Rectangle object.");
-    }
-}
-
-impl OtherTrait for Rectangle {}
-
-// Static Dispatch / Monomorphization
-// Behind the scenes, when we use static dispatch, Rust creates dedicated functions
-// for the types on which this function was called.This is done at compile time. For
-// example, we called this function using a `Sqaure` and a `Rectangle` type, thus rust
-// behind the scenes created separate versions of the below function for them. This
-// is static dispatch or Monomorphization.
-// *********NOTE********
-// The special advantage of static dispatch is that it improves performance by eliminating
-// runtime overhead. The `area` function below can also be categorized into static dispatch.
-fn share_properties<T: Shape>(obj: T) -> (i32, i32) {
-    (obj.area(), obj.perimeter())
-}
-
-// Dynamic Dispatch - similar to static dispatch, but here the specific implementations of
-// the function are not created at compile time but at runtime. Box is a smart pointer. It
-// is a pointer to some Heap allocated data. We are also not using the trait bound when using
-// dynamic dispatch. The `dyn` keyword stands for dynamic dispatch and is used to define a
-// trait object here. The essential requirement of a trait object is that it must be behind
-// a pointer. In this case, we use the Box smart pointer. Trait objects allows us to define
-// a type which implements a trait without knowing or having knowledge of what that type is
-// at the compile time. In this case, the specialized versions of the functions will be not
-// be generated and the resolution of the function will take place at runtime/execution time
-// and not at compile time.
-fn share_properties_dynamic(obj: Box<dyn Shape>) -> (i32, i32) {
-    (obj.area(), obj.perimeter())
-}
-
-// ********NOTE*************
-// Trait Object = Dynamic Dispatch
-// Trait Bound = Static Dispatch
-
-// Function implemented with Trait Bound. This is useful in such scenarios where we
-// have a common method that we can call on the object passed to the function. But
-// Rust should have some way to know or limit the type of objects that are passed in
-// such that they should have associated to them the method we are calling on them.
-// Here, in this scenario Trait Bounds help us by limiting or defining the types that
-// can be passed to the function. The types are limited or differentiated from the rest
-// on the basis of Traits. Thus, in the below example, the type of object passed to the
-// function must implement the Shape trait.
-fn area<T: Shape>(obj: &T) -> i32 {
-    obj.area()
-}
-
-// Function using Dynamic Dispatch to return different objects of similar type using
-// `Box` smart pointer. We can return a trait bound by returning `impl Shape` if we
-// are only returning a single concrete type say `Square`
-fn returns_shape(dimension: Vec<i32>) -> Box<dyn Shape> {
-    if dimension.len() == 1 {
-        Box::new(Square::new(dimension[0]))
-    } else {
-        Box::new(Rectangle::new(dimension[0], dimension[1]))
-    }
-}
-
-// Derive Traits - traits that can be automatically implemented for a type using the
-// `derive` attribute. These traits are applied to structs and enums to provide default
-// implementation for certain behaviors. For example, in the below lines of code, derive
-// is implementing the `Debug`trait for the Student struct. Thus, we don't have to implement
-// the trait manually.
-// The derive traits are available for common behavior such as comparisons, cloning and
-// initializing instances of structs from some default value.
-// Debug trait - used to print a struct/enum via println.
-// PartialEq trait - used to compare two instances of the same type.
-#[derive(Debug, PartialEq)]
-struct Student {
-    name: String,
-    age: i32,
-}
+// Demo of the shapes defined in the `traits` library crate (src/lib.rs).
+use traits::{
+    area, parse_scene, parse_shape, returns_shape, shapes_from_json, shapes_to_json,
+    share_properties, share_properties_dynamic, svg, Canvas, Circle, DrawMode, Gradebook, Group,
+    Polygon, Rectangle, RegularPolygon, Shape, ShapeRegistry, ShapeStats, Square, Student,
+    StudentRegistry, SvgStyle, TextExporter, Transform, Triangle,
+};
 
 // ######################################## //
 //              MAIN                        //
@@ -206,16 +57,138 @@ fn main() {
 
     println!();
 
+    // Shapes with floating-point geometry share the same `Shape` trait
+    let curved_shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Circle::new(3.0)),
+        Box::new(Triangle::new((0.0, 0.0), (4.0, 0.0), (0.0, 3.0))),
+        Box::new(RegularPolygon::new(6, 2.0)),
+        Box::new(Polygon::new(vec![
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 1.0),
+            (1.0, 3.0),
+        ])),
+    ];
+    for shape in &curved_shapes {
+        println!(
+            "Area {:.2}  Perimeter {:.2}",
+            shape.area(),
+            shape.perimeter()
+        );
+    }
+
+    println!();
+
     // Creating different types of shapes from a function by returing trait
     // objects.
-    let random_shape_1 = returns_shape(vec![9]); // Square
-    let random_shape_2 = returns_shape(vec![9, 6]); // Rectangle
+    let random_shape_1 = returns_shape(vec![9]).unwrap(); // Square
+    let random_shape_2 = returns_shape(vec![9, 6]).unwrap(); // Rectangle
 
     println!("Area of random shape 1:  {}", random_shape_1.area());
     println!("Area of random shape 2:  {}", random_shape_2.area());
 
     println!();
 
+    // Building shapes by name through the registry
+    let registry = ShapeRegistry::with_builtin_shapes();
+    let kinds: Vec<&str> = registry.kinds().collect();
+    println!("Registered shape kinds: {}", kinds.join(", "));
+    match registry.build("circle", &[2.0]) {
+        Ok(circle) => println!("Circle from registry: area {:.2}", circle.area()),
+        Err(err) => println!("Registry error: {}", err),
+    }
+
+    println!();
+
+    // Validated constructors and checked measurements report errors instead of
+    // wrapping around on overflow.
+    match Square::try_new(-3) {
+        Ok(square) => println!("Square area {}", square.area()),
+        Err(err) => println!("Invalid square: {}", err),
+    }
+    match Square::new(50_000).checked_area() {
+        Ok(area) => println!("Big square area {}", area),
+        Err(err) => println!("Big square area: {}", err),
+    }
+
+    println!();
+
+    // Parsing shapes from text. Invalid input is reported with its position instead
+    // of panicking.
+    match parse_scene("square 3\nrect 8x6\n") {
+        Ok(shapes) => println!("Parsed {} shapes from the scene", shapes.len()),
+        Err(err) => println!("Scene error: {}", err),
+    }
+    if let Err(err) = parse_shape("rect 8xsix") {
+        println!("Parse error: {}", err);
+    }
+
+    println!();
+
+    // Drawing trait objects onto a character-grid canvas, once filled and once as
+    // outlines only.
+    let scene: Vec<Box<dyn Shape>> = vec![Box::new(Square::new(4)), Box::new(Rectangle::new(8, 3))];
+    let mut canvas = Canvas::new(16, 4);
+    canvas.place(scene[0].as_ref(), 0, 0, DrawMode::Filled);
+    canvas.place(scene[1].as_ref(), 6, 0, DrawMode::Outline);
+    print!("{}", canvas);
+
+    println!();
+
+    // Exporting the same scene as an SVG document
+    print!(
+        "{}",
+        svg::scene_to_svg(&scene, 2.0, &SvgStyle::new("black", "lightgray", 1.0))
+    );
+
+    println!();
+
+    // Saving the scene as JSON and loading it back as trait objects
+    let json = shapes_to_json(&scene);
+    println!("{}", json);
+    match shapes_from_json(&json) {
+        Ok(shapes) => println!(
+            "Loaded {:?} back from JSON, equal to the original: {}",
+            shapes,
+            shapes == scene
+        ),
+        Err(err) => println!("JSON error: {}", err),
+    }
+
+    println!();
+
+    // Visitors: one exporter and one analyzer, each written in a single place for all
+    // shape types.
+    print!("{}", TextExporter::export(&scene));
+    let stats = ShapeStats::of(&scene);
+    println!(
+        "{} squares, {} rectangles, total area {}",
+        stats.count("square"),
+        stats.count("rectangle"),
+        stats.total_area
+    );
+
+    println!();
+
+    // Grouping shapes with transforms. The group is a `Shape` itself, so it can be drawn
+    // like any other shape.
+    let group = Group::new()
+        .with(Box::new(Square::new(3)), Transform::identity())
+        .with(
+            Box::new(Rectangle::new(6, 2)),
+            Transform::translate(9.0, 0.0).with_rotation(std::f64::consts::FRAC_PI_2),
+        );
+    println!(
+        "Group: area {}  perimeter {}  bounding box {:?}",
+        group.area(),
+        group.perimeter(),
+        group.extent()
+    );
+    let mut canvas = Canvas::new(10, 6);
+    canvas.place(&group, 0, 0, DrawMode::Filled);
+    print!("{}", canvas);
+    println!();
+
     // Derive traits example for the Debug and PartialEq trait
     let student_1 = Student {
         name: "Ayush".to_owned(),
@@ -233,4 +206,40 @@ fn main() {
         "Comparison, if student_1 == student_2:  {}",
         student_1 == student_2
     );
+
+    // The derived Ord and Hash traits at work in a student registry
+    let mut students = StudentRegistry::new();
+    for student in [student_1, student_2] {
+        if let Err(err) = students.add(student) {
+            println!("Student error: {}", err);
+        }
+    }
+    println!("Duplicate student ids: {:?}", students.duplicates());
+    print!("{}", students.to_csv());
+
+    // Course scores, statistics and transcripts on top of the student records
+    let mut gradebook = Gradebook::new();
+    for (name, age, score) in [("Asha", 21, 88.0), ("Ben", 22, 72.5), ("Chen", 20, 88.0)] {
+        let student = Student {
+            name: name.to_owned(),
+            age,
+        };
+        let recorded = match gradebook.add_student(student) {
+            Ok(id) => gradebook.record_score(id, "rust", score),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = recorded {
+            println!("Gradebook error: {}", err);
+        }
+    }
+    if let Ok(stats) = gradebook.course_stats("rust") {
+        println!(
+            "rust: mean {:.1}, median {:.1}, std dev {:.1}",
+            stats.mean, stats.median, stats.std_dev
+        );
+    }
+    let first = gradebook.students().iter().next().map(|(id, _)| id);
+    if let Some(Ok(transcript)) = first.map(|id| gradebook.transcript(id)) {
+        print!("{}", transcript);
+    }
 }
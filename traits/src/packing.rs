@@ -0,0 +1,559 @@
+// Rectangle bin packing: assigns non-overlapping positions inside a container to a list
+// of `Rectangle`s. Three heuristics are offered, from simplest to tightest:
+//
+//   Shelf      - rectangles sorted by height are put left to right on horizontal shelves
+//   Guillotine - free space is kept as rectangles that are cut in two after every placement
+//   MaxRects   - free space is kept as all maximal free rectangles (best short side fit)
+//
+// A rectangle's length runs along x and its breadth along y, like when it is drawn. With
+// rotation enabled a rectangle may be turned a quarter turn to make it fit. The result can
+// be drawn onto a `Canvas` since `Packing` implements the `Draw` trait.
+use crate::{Canvas, Draw, Rectangle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    Shelf,
+    Guillotine,
+    MaxRects,
+}
+
+// Where one input rectangle ended up. `width` and `height` are the placed size, i.e.
+// length and breadth swapped when `rotated`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub index: usize,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub rotated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packing {
+    pub width: i32,
+    pub height: i32,
+    pub placements: Vec<Placement>,
+    // Indices of the rectangles that did not fit.
+    pub unplaced: Vec<usize>,
+}
+
+impl Packing {
+    pub fn used_area(&self) -> i64 {
+        self.placements
+            .iter()
+            .map(|p| i64::from(p.width) * i64::from(p.height))
+            .sum()
+    }
+
+    // Fraction of the container covered by placed rectangles, between 0 and 1.
+    pub fn utilization(&self) -> f64 {
+        let container = i64::from(self.width) * i64::from(self.height);
+        if container <= 0 {
+            return 0.0;
+        }
+        self.used_area() as f64 / container as f64
+    }
+}
+
+// Each placed rectangle is drawn in the canvas' current mode with its own letter ('A',
+// 'B', ...) so neighbours can be told apart.
+impl Draw for Packing {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        let ink = canvas.ink();
+        for (n, placement) in self.placements.iter().enumerate() {
+            canvas.set_ink((b'A' + (n % 26) as u8) as char);
+            canvas.draw_rect(placement.x, placement.y, placement.width, placement.height);
+        }
+        canvas.set_ink(ink);
+    }
+}
+
+// A free or used area of the container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Area {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+// The far edges are i64 so they can't overflow. Areas of a `Packing` are inside the
+// container, so their edges also fit in an i32.
+impl Area {
+    fn right(&self) -> i64 {
+        i64::from(self.x) + i64::from(self.width)
+    }
+
+    fn bottom(&self) -> i64 {
+        i64::from(self.y) + i64::from(self.height)
+    }
+
+    fn contains(&self, other: &Area) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    fn overlaps(&self, other: &Area) -> bool {
+        i64::from(self.x) < other.right()
+            && i64::from(other.x) < self.right()
+            && i64::from(self.y) < other.bottom()
+            && i64::from(other.y) < self.bottom()
+    }
+}
+
+pub struct Packer {
+    width: i32,
+    height: i32,
+    heuristic: Heuristic,
+    allow_rotation: bool,
+}
+
+impl Packer {
+    pub fn new(width: i32, height: i32, heuristic: Heuristic) -> Packer {
+        Packer {
+            width,
+            height,
+            heuristic,
+            allow_rotation: false,
+        }
+    }
+
+    pub fn allow_rotation(mut self, allow: bool) -> Packer {
+        self.allow_rotation = allow;
+        self
+    }
+
+    // Orientations to try for a rectangle: as given, then rotated if allowed.
+    fn orientations(&self, rect: &Rectangle) -> Vec<(i32, i32, bool)> {
+        let mut sizes = vec![(rect.length(), rect.breadth(), false)];
+        if self.allow_rotation && rect.length() != rect.breadth() {
+            sizes.push((rect.breadth(), rect.length(), true));
+        }
+        sizes
+    }
+
+    pub fn pack(&self, rects: &[Rectangle]) -> Packing {
+        let mut packing = Packing {
+            width: self.width,
+            height: self.height,
+            placements: Vec::new(),
+            unplaced: Vec::new(),
+        };
+        // Rectangles without area take no space; they are placed at the origin. Ones with a
+        // negative side don't describe a rectangle and are never placed.
+        let mut order = Vec::new();
+        for (index, rect) in rects.iter().enumerate() {
+            let (width, height) = (rect.length(), rect.breadth());
+            if width < 0 || height < 0 {
+                packing.unplaced.push(index);
+            } else if width == 0 || height == 0 {
+                packing.placements.push(Placement {
+                    index,
+                    x: 0,
+                    y: 0,
+                    width,
+                    height,
+                    rotated: false,
+                });
+            } else {
+                order.push(index);
+            }
+        }
+        // Big rectangles first, they are the hardest to fit. The sort is stable, so equal
+        // rectangles keep their input order.
+        match self.heuristic {
+            Heuristic::Shelf => order.sort_by_key(|&i| {
+                let rect = &rects[i];
+                std::cmp::Reverse(if self.allow_rotation {
+                    rect.length().min(rect.breadth())
+                } else {
+                    rect.breadth()
+                })
+            }),
+            _ => order.sort_by_key(|&i| {
+                std::cmp::Reverse(i64::from(rects[i].length()) * i64::from(rects[i].breadth()))
+            }),
+        }
+        match self.heuristic {
+            Heuristic::Shelf => self.pack_shelf(rects, &order, &mut packing),
+            Heuristic::Guillotine => self.pack_guillotine(rects, &order, &mut packing),
+            Heuristic::MaxRects => self.pack_max_rects(rects, &order, &mut packing),
+        }
+        packing.unplaced.sort_unstable();
+        packing
+    }
+
+    fn pack_shelf(&self, rects: &[Rectangle], order: &[usize], packing: &mut Packing) {
+        // (y, height, used width) of every shelf opened so far.
+        let mut shelves: Vec<(i32, i32, i32)> = Vec::new();
+        let mut top = 0;
+        for &index in order {
+            let mut sizes = self.orientations(&rects[index]);
+            // Lying flat keeps shelves low.
+            sizes.sort_by_key(|&(_, height, _)| height);
+            let existing = sizes.iter().find_map(|&(width, height, rotated)| {
+                shelves
+                    .iter_mut()
+                    .find(|(_, shelf_height, used)| {
+                        height <= *shelf_height
+                            && i64::from(*used) + i64::from(width) <= i64::from(self.width)
+                    })
+                    .map(|shelf| {
+                        let x = shelf.2;
+                        shelf.2 += width;
+                        (x, shelf.0, width, height, rotated)
+                    })
+            });
+            let placed = existing.or_else(|| {
+                sizes
+                    .iter()
+                    .find(|&&(width, height, _)| {
+                        width <= self.width
+                            && i64::from(top) + i64::from(height) <= i64::from(self.height)
+                    })
+                    .map(|&(width, height, rotated)| {
+                        shelves.push((top, height, width));
+                        top += height;
+                        (0, top - height, width, height, rotated)
+                    })
+            });
+            match placed {
+                Some((x, y, width, height, rotated)) => packing.placements.push(Placement {
+                    index,
+                    x,
+                    y,
+                    width,
+                    height,
+                    rotated,
+                }),
+                None => packing.unplaced.push(index),
+            }
+        }
+    }
+
+    fn pack_guillotine(&self, rects: &[Rectangle], order: &[usize], packing: &mut Packing) {
+        let mut free = vec![Area {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        }];
+        for &index in order {
+            // Best area fit: the free rectangle with the least area left over.
+            let best = free
+                .iter()
+                .enumerate()
+                .flat_map(|(slot, area)| {
+                    self.orientations(&rects[index])
+                        .into_iter()
+                        .filter(|&(width, height, _)| width <= area.width && height <= area.height)
+                        .map(move |size| (slot, size))
+                })
+                .min_by_key(|&(slot, (width, height, _))| {
+                    i64::from(free[slot].width) * i64::from(free[slot].height)
+                        - i64::from(width) * i64::from(height)
+                });
+            let Some((slot, (width, height, rotated))) = best else {
+                packing.unplaced.push(index);
+                continue;
+            };
+            let area = free.swap_remove(slot);
+            packing.placements.push(Placement {
+                index,
+                x: area.x,
+                y: area.y,
+                width,
+                height,
+                rotated,
+            });
+            // Split the leftover L-shape with a single cut along the shorter leftover
+            // axis, which keeps the bigger of the two pieces as large as possible.
+            let (right_width, bottom_height) = (area.width - width, area.height - height);
+            let (right, bottom) = if right_width < bottom_height {
+                (
+                    Area {
+                        x: area.x + width,
+                        y: area.y,
+                        width: right_width,
+                        height,
+                    },
+                    Area {
+                        x: area.x,
+                        y: area.y + height,
+                        width: area.width,
+                        height: bottom_height,
+                    },
+                )
+            } else {
+                (
+                    Area {
+                        x: area.x + width,
+                        y: area.y,
+                        width: right_width,
+                        height: area.height,
+                    },
+                    Area {
+                        x: area.x,
+                        y: area.y + height,
+                        width,
+                        height: bottom_height,
+                    },
+                )
+            };
+            free.extend(
+                [right, bottom]
+                    .into_iter()
+                    .filter(|piece| piece.width > 0 && piece.height > 0),
+            );
+        }
+    }
+
+    fn pack_max_rects(&self, rects: &[Rectangle], order: &[usize], packing: &mut Packing) {
+        let mut free = vec![Area {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        }];
+        for &index in order {
+            // Best short side fit: the position leaving the smallest gap on either side.
+            let best = free
+                .iter()
+                .flat_map(|area| {
+                    self.orientations(&rects[index])
+                        .into_iter()
+                        .filter(|&(width, height, _)| width <= area.width && height <= area.height)
+                        .map(move |size| (*area, size))
+                })
+                .min_by_key(|&(area, (width, height, _))| {
+                    let (gap_x, gap_y) = (area.width - width, area.height - height);
+                    (gap_x.min(gap_y), gap_x.max(gap_y))
+                });
+            let Some((area, (width, height, rotated))) = best else {
+                packing.unplaced.push(index);
+                continue;
+            };
+            let used = Area {
+                x: area.x,
+                y: area.y,
+                width,
+                height,
+            };
+            packing.placements.push(Placement {
+                index,
+                x: used.x,
+                y: used.y,
+                width,
+                height,
+                rotated,
+            });
+
+            // Every free rectangle overlapping the placed one is replaced by the (up to
+            // four) maximal rectangles around it, then rectangles contained in others are
+            // dropped. The pieces are inside the container, so their edges fit in an i32.
+            let mut next = Vec::with_capacity(free.len() + 4);
+            for area in free {
+                if !area.overlaps(&used) {
+                    next.push(area);
+                    continue;
+                }
+                let pieces = [
+                    Area {
+                        width: used.x - area.x,
+                        ..area
+                    },
+                    Area {
+                        x: used.right() as i32,
+                        width: (area.right() - used.right()) as i32,
+                        ..area
+                    },
+                    Area {
+                        height: used.y - area.y,
+                        ..area
+                    },
+                    Area {
+                        y: used.bottom() as i32,
+                        height: (area.bottom() - used.bottom()) as i32,
+                        ..area
+                    },
+                ];
+                next.extend(
+                    pieces
+                        .into_iter()
+                        .filter(|piece| piece.width > 0 && piece.height > 0),
+                );
+            }
+            let mut kept: Vec<Area> = Vec::with_capacity(next.len());
+            for (i, area) in next.iter().enumerate() {
+                let redundant = next
+                    .iter()
+                    .enumerate()
+                    .any(|(j, other)| i != j && other.contains(area) && (other != area || j < i));
+                if !redundant {
+                    kept.push(*area);
+                }
+            }
+            free = kept;
+        }
+    }
+}
+
+// Convenience wrapper around `Packer`.
+pub fn pack(
+    rects: &[Rectangle],
+    width: i32,
+    height: i32,
+    heuristic: Heuristic,
+    allow_rotation: bool,
+) -> Packing {
+    Packer::new(width, height, heuristic)
+        .allow_rotation(allow_rotation)
+        .pack(rects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DrawMode;
+
+    const HEURISTICS: [Heuristic; 3] =
+        [Heuristic::Shelf, Heuristic::Guillotine, Heuristic::MaxRects];
+
+    fn sample() -> Vec<Rectangle> {
+        [
+            (4, 3),
+            (2, 6),
+            (5, 2),
+            (3, 3),
+            (1, 4),
+            (6, 1),
+            (2, 2),
+            (3, 1),
+        ]
+        .iter()
+        .map(|&(length, breadth)| Rectangle::new(length, breadth))
+        .collect()
+    }
+
+    // Every placement lies inside the container, no two overlap and the placed size is
+    // the input size, rotated if flagged.
+    fn assert_valid(rects: &[Rectangle], packing: &Packing) {
+        let areas: Vec<Area> = packing
+            .placements
+            .iter()
+            .map(|p| Area {
+                x: p.x,
+                y: p.y,
+                width: p.width,
+                height: p.height,
+            })
+            .collect();
+        for (p, area) in packing.placements.iter().zip(&areas) {
+            let rect = &rects[p.index];
+            let expected = if p.rotated {
+                (rect.breadth(), rect.length())
+            } else {
+                (rect.length(), rect.breadth())
+            };
+            assert_eq!((p.width, p.height), expected);
+            assert!(area.x >= 0 && area.y >= 0);
+            assert!(
+                area.right() <= i64::from(packing.width)
+                    && area.bottom() <= i64::from(packing.height)
+            );
+        }
+        for (i, a) in areas.iter().enumerate() {
+            for b in &areas[i + 1..] {
+                assert!(!a.overlaps(b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+        assert_eq!(
+            packing.placements.len() + packing.unplaced.len(),
+            rects.len()
+        );
+    }
+
+    #[test]
+    fn every_heuristic_produces_valid_packings() {
+        let rects = sample();
+        for heuristic in HEURISTICS {
+            for rotation in [false, true] {
+                let packing = pack(&rects, 10, 10, heuristic, rotation);
+                assert_valid(&rects, &packing);
+                assert_eq!(packing.utilization(), packing.used_area() as f64 / 100.0);
+            }
+        }
+        // With room to spare, all of them fit on shelves.
+        assert!(pack(&rects, 10, 10, Heuristic::Shelf, false)
+            .unplaced
+            .is_empty());
+    }
+
+    #[test]
+    fn max_rects_packs_tightly() {
+        let rects = sample();
+        let packing = pack(&rects, 10, 8, Heuristic::MaxRects, true);
+
+        assert_valid(&rects, &packing);
+        assert!(packing.unplaced.is_empty());
+        assert_eq!(packing.utilization(), 60.0 / 80.0);
+    }
+
+    #[test]
+    fn rotation_makes_rectangles_fit() {
+        let rects = vec![Rectangle::new(5, 2)];
+        for heuristic in HEURISTICS {
+            assert_eq!(pack(&rects, 2, 5, heuristic, false).unplaced, vec![0]);
+            let packing = pack(&rects, 2, 5, heuristic, true);
+            assert!(packing.placements[0].rotated);
+            assert_eq!(packing.utilization(), 1.0);
+        }
+    }
+
+    #[test]
+    fn handles_rectangles_at_the_limits() {
+        let rects = vec![
+            Rectangle::new(i32::MAX, 1),
+            Rectangle::new(5, 1),
+            Rectangle::new(-2, 3),
+            Rectangle::new(0, 4),
+        ];
+        for heuristic in HEURISTICS {
+            let packing = Packer::new(i32::MAX, i32::MAX, heuristic).pack(&rects);
+            assert_eq!(packing.unplaced, vec![2], "{:?}", heuristic);
+            let placed: Vec<usize> = packing.placements.iter().map(|p| p.index).collect();
+            assert_eq!(placed.len(), 3);
+            assert!(!placed.contains(&2));
+        }
+        // A full shelf: the second rectangle goes on a new one.
+        let packing = Packer::new(i32::MAX, i32::MAX, Heuristic::Shelf).pack(&rects[..2]);
+        assert_eq!((packing.placements[1].x, packing.placements[1].y), (0, 1));
+    }
+
+    #[test]
+    fn reports_rectangles_that_do_not_fit() {
+        let rects = vec![
+            Rectangle::new(3, 3),
+            Rectangle::new(3, 3),
+            Rectangle::new(1, 1),
+        ];
+        for heuristic in HEURISTICS {
+            let packing = pack(&rects, 4, 4, heuristic, true);
+            assert_valid(&rects, &packing);
+            assert_eq!(packing.unplaced, vec![1]);
+        }
+    }
+
+    #[test]
+    fn draws_each_rectangle_with_its_own_letter() {
+        let rects = vec![Rectangle::new(3, 2), Rectangle::new(1, 2)];
+        let packing = pack(&rects, 4, 2, Heuristic::MaxRects, false);
+        let mut canvas = Canvas::new(4, 2);
+        canvas.place(&packing, 0, 0, DrawMode::Filled);
+
+        assert_eq!(canvas.render(), "AAAB\nAAAB\n");
+    }
+}
@@ -0,0 +1,309 @@
+// Parser for a small line-oriented shape description syntax. Each non-empty line
+// describes one shape:
+//
+//     square 4
+//     rect 8x6        # `rectangle 8x6` works too
+//
+// Everything after a `#` is a comment. Errors carry the 1-based line and column of the
+// offending token instead of panicking like indexing into a `Vec` would.
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{Rectangle, Shape, ShapeError, Square};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeParseErrorKind {
+    // The first word of the line is not a known shape kind.
+    UnknownShape(String),
+    // The shape kind was given without its dimensions.
+    MissingDimensions,
+    // A dimension could not be read as an integer.
+    InvalidNumber(String),
+    // A rectangle's dimensions were not written as `<length>x<breadth>`.
+    InvalidRectangle(String),
+    // Extra input after a complete shape description.
+    UnexpectedToken(String),
+    // The dimensions were read but describe an invalid shape, e.g. a negative side.
+    InvalidShape(ShapeError),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ShapeParseErrorKind,
+}
+
+impl fmt::Display for ShapeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            ShapeParseErrorKind::UnknownShape(name) => write!(f, "unknown shape `{}`", name),
+            ShapeParseErrorKind::MissingDimensions => write!(f, "missing dimensions"),
+            ShapeParseErrorKind::InvalidNumber(text) => write!(f, "invalid number `{}`", text),
+            ShapeParseErrorKind::InvalidRectangle(text) => write!(
+                f,
+                "invalid rectangle dimensions `{}`, expected `<length>x<breadth>`",
+                text
+            ),
+            ShapeParseErrorKind::UnexpectedToken(text) => {
+                write!(f, "unexpected token `{}`", text)
+            }
+            ShapeParseErrorKind::InvalidShape(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for ShapeParseError {}
+
+// Error returned when loading a scene file from disk.
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    Parse(ShapeParseError),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "could not read scene: {}", err),
+            SceneError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for SceneError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SceneError::Io(err) => Some(err),
+            SceneError::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for SceneError {
+    fn from(err: io::Error) -> SceneError {
+        SceneError::Io(err)
+    }
+}
+
+impl From<ShapeParseError> for SceneError {
+    fn from(err: ShapeParseError) -> SceneError {
+        SceneError::Parse(err)
+    }
+}
+
+// A whitespace separated word and the 1-based column it starts at.
+struct Token<'a> {
+    text: &'a str,
+    column: usize,
+}
+
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let line = match line.find('#') {
+        Some(comment) => &line[..comment],
+        None => line,
+    };
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (column, (offset, c)) in line.char_indices().enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some((offset, column + 1)),
+            (true, Some((begin, col))) => {
+                tokens.push(Token {
+                    text: &line[begin..offset],
+                    column: col,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((begin, col)) = start {
+        tokens.push(Token {
+            text: &line[begin..],
+            column: col,
+        });
+    }
+    tokens
+}
+
+fn error(line: usize, column: usize, kind: ShapeParseErrorKind) -> ShapeParseError {
+    ShapeParseError { line, column, kind }
+}
+
+fn parse_number(text: &str, line: usize, column: usize) -> Result<i32, ShapeParseError> {
+    text.parse().map_err(|_| {
+        error(
+            line,
+            column,
+            ShapeParseErrorKind::InvalidNumber(text.to_owned()),
+        )
+    })
+}
+
+fn invalid_shape(err: ShapeError, line: usize, column: usize) -> ShapeParseError {
+    error(line, column, ShapeParseErrorKind::InvalidShape(err))
+}
+
+// Parses the tokens of a single line. Returns Ok(None) for blank and comment-only lines.
+fn parse_tokens(
+    tokens: &[Token<'_>],
+    line: usize,
+) -> Result<Option<Box<dyn Shape>>, ShapeParseError> {
+    let Some(kind) = tokens.first() else {
+        return Ok(None);
+    };
+    let Some(dims) = tokens.get(1) else {
+        let column = kind.column + kind.text.chars().count();
+        return Err(error(line, column, ShapeParseErrorKind::MissingDimensions));
+    };
+    if let Some(extra) = tokens.get(2) {
+        return Err(error(
+            line,
+            extra.column,
+            ShapeParseErrorKind::UnexpectedToken(extra.text.to_owned()),
+        ));
+    }
+
+    let shape: Box<dyn Shape> = match kind.text {
+        "square" => Box::new(
+            Square::try_new(parse_number(dims.text, line, dims.column)?)
+                .map_err(|err| invalid_shape(err, line, dims.column))?,
+        ),
+        "rect" | "rectangle" => {
+            let Some((length, breadth)) = dims.text.split_once(['x', 'X']) else {
+                return Err(error(
+                    line,
+                    dims.column,
+                    ShapeParseErrorKind::InvalidRectangle(dims.text.to_owned()),
+                ));
+            };
+            let breadth_column = dims.column + length.chars().count() + 1;
+            Box::new(
+                Rectangle::try_new(
+                    parse_number(length, line, dims.column)?,
+                    parse_number(breadth, line, breadth_column)?,
+                )
+                // Point at the dimension that was rejected.
+                .map_err(|err| {
+                    let column = match err {
+                        ShapeError::NegativeDimension {
+                            dimension: "breadth",
+                            ..
+                        } => breadth_column,
+                        _ => dims.column,
+                    };
+                    invalid_shape(err, line, column)
+                })?,
+            )
+        }
+        other => {
+            return Err(error(
+                line,
+                kind.column,
+                ShapeParseErrorKind::UnknownShape(other.to_owned()),
+            ))
+        }
+    };
+    Ok(Some(shape))
+}
+
+// Parses a single shape description such as `square 4` or `rect 8x6`.
+pub fn parse_shape(input: &str) -> Result<Box<dyn Shape>, ShapeParseError> {
+    let tokens = tokenize(input);
+    parse_tokens(&tokens, 1)?.ok_or_else(|| error(1, 1, ShapeParseErrorKind::MissingDimensions))
+}
+
+// Parses a whole scene, one shape per line. Blank lines and comments are skipped and the
+// first error stops parsing.
+pub fn parse_scene(input: &str) -> Result<Vec<Box<dyn Shape>>, ShapeParseError> {
+    let mut shapes = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if let Some(shape) = parse_tokens(&tokenize(line), index + 1)? {
+            shapes.push(shape);
+        }
+    }
+    Ok(shapes)
+}
+
+// Reads and parses a scene file.
+pub fn load_scene<P: AsRef<Path>>(path: P) -> Result<Vec<Box<dyn Shape>>, SceneError> {
+    let contents = fs::read_to_string(path)?;
+    Ok(parse_scene(&contents)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(input: &str) -> ShapeParseError {
+        match parse_scene(input) {
+            Ok(_) => panic!("expected `{}` to fail", input),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn parses_squares_and_rectangles() {
+        let square = parse_shape("square 4").unwrap();
+        let rectangle = parse_shape("  rect 8x6  # comment").unwrap();
+
+        assert_eq!((square.area(), square.perimeter()), (16.0, 16.0));
+        assert_eq!((rectangle.area(), rectangle.perimeter()), (48.0, 28.0));
+    }
+
+    #[test]
+    fn parses_scene_skipping_blank_and_comment_lines() {
+        let scene = parse_scene("# my scene\nsquare 2\n\nrectangle 3X5\n").unwrap();
+        let areas: Vec<f64> = scene.iter().map(|shape| shape.area()).collect();
+
+        assert_eq!(areas, vec![4.0, 15.0]);
+    }
+
+    #[test]
+    fn reports_line_and_column() {
+        let err = parse_error("square 2\nrect 8xsix");
+
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 8);
+        assert_eq!(
+            err.kind,
+            ShapeParseErrorKind::InvalidNumber("six".to_owned())
+        );
+        assert_eq!(err.to_string(), "line 2, column 8: invalid number `six`");
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert_eq!(
+            parse_error("circle 3").kind,
+            ShapeParseErrorKind::UnknownShape("circle".to_owned())
+        );
+        assert_eq!(
+            parse_error("square").kind,
+            ShapeParseErrorKind::MissingDimensions
+        );
+        assert_eq!(
+            parse_error("rect 8").kind,
+            ShapeParseErrorKind::InvalidRectangle("8".to_owned())
+        );
+        let err = parse_error("square 4 4");
+        assert_eq!(
+            (err.column, err.kind),
+            (10, ShapeParseErrorKind::UnexpectedToken("4".to_owned()))
+        );
+        assert!(parse_shape("").is_err());
+        assert_eq!(
+            parse_error("rect 3x-1").to_string(),
+            "line 1, column 8: breadth must not be negative, got -1"
+        );
+        assert_eq!(
+            parse_error("rect -3x1").to_string(),
+            "line 1, column 6: length must not be negative, got -3"
+        );
+    }
+}
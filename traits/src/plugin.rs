@@ -0,0 +1,251 @@
+// Shape plugins: shared libraries (`.so` files) that add shape kinds to a `ShapeRegistry`
+// at runtime, so shape types can live in their own crates and be loaded on demand.
+//
+// A plugin is a `cdylib` crate depending on this one. It declares itself with
+// `declare_plugin!(register)`, where `register` is a `fn(&mut ShapeRegistry)` adding the
+// plugin's constructors:
+//
+//     fn register(registry: &mut ShapeRegistry) {
+//         registry.register("star", |params| ...);
+//     }
+//
+//     traits::declare_plugin!(register);
+//
+// `Box<dyn Shape>` has no stable ABI, so the plugin has to be built against the same
+// version of this crate, with the same compiler, as the program loading it. The loader
+// checks `PLUGIN_ABI_VERSION` (bumped whenever the declaration below changes) and the
+// crate version before calling into the plugin; a compiler mismatch can't be detected.
+//
+// Plugins are never unloaded: the shapes and constructors they create point into the
+// library's code, which has to stay mapped for as long as the program runs.
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use libloading::Library;
+
+use crate::ShapeRegistry;
+
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+pub const TRAITS_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Name of the exported `PluginDeclaration` static, as written by `declare_plugin!`.
+pub const PLUGIN_SYMBOL: &str = "TRAITS_SHAPE_PLUGIN";
+
+// `abi_version` comes first and `repr(C)` keeps it there, so it can be checked before the
+// rest of the declaration is trusted.
+#[repr(C)]
+pub struct PluginDeclaration {
+    pub abi_version: u32,
+    pub traits_version: &'static str,
+    pub register: fn(&mut ShapeRegistry),
+}
+
+// Exports the plugin declaration from a plugin crate.
+#[macro_export]
+macro_rules! declare_plugin {
+    ($register:path) => {
+        #[no_mangle]
+        pub static TRAITS_SHAPE_PLUGIN: $crate::plugin::PluginDeclaration =
+            $crate::plugin::PluginDeclaration {
+                abi_version: $crate::plugin::PLUGIN_ABI_VERSION,
+                traits_version: $crate::plugin::TRAITS_VERSION,
+                register: $register,
+            };
+    };
+}
+
+#[derive(Debug)]
+pub enum PluginError {
+    // The library could not be opened, e.g. a missing file or not a shared library.
+    Load {
+        path: PathBuf,
+        source: libloading::Error,
+    },
+    // The library doesn't export a plugin declaration.
+    NotAPlugin {
+        path: PathBuf,
+        source: libloading::Error,
+    },
+    AbiMismatch {
+        path: PathBuf,
+        expected: u32,
+        found: u32,
+    },
+    VersionMismatch {
+        path: PathBuf,
+        expected: &'static str,
+        found: String,
+    },
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PluginError::Load { path, source } => {
+                write!(f, "cannot load plugin {}: {}", path.display(), source)
+            }
+            PluginError::NotAPlugin { path, .. } => write!(
+                f,
+                "{} is not a shape plugin (no `{}` symbol)",
+                path.display(),
+                PLUGIN_SYMBOL
+            ),
+            PluginError::AbiMismatch {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "plugin {} uses plugin ABI version {}, expected {}",
+                path.display(),
+                found,
+                expected
+            ),
+            PluginError::VersionMismatch {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "plugin {} was built against traits {}, expected {}",
+                path.display(),
+                found,
+                expected
+            ),
+        }
+    }
+}
+
+impl Error for PluginError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PluginError::Load { source, .. } | PluginError::NotAPlugin { source, .. } => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
+
+// What a loaded plugin added to the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedPlugin {
+    pub path: PathBuf,
+    // Kinds that were not registered before loading the plugin, in alphabetical order.
+    pub new_kinds: Vec<String>,
+}
+
+impl ShapeRegistry {
+    // Opens the shared library at `path`, checks its declaration and lets it register
+    // its shape kinds.
+    pub fn load_plugin(&mut self, path: impl AsRef<Path>) -> Result<LoadedPlugin, PluginError> {
+        let path = path.as_ref().to_path_buf();
+        // SAFETY: loading a library runs its initializers. Plugins are trusted code, the
+        // same as any other dependency of the program.
+        let library = unsafe { Library::new(&path) }.map_err(|source| PluginError::Load {
+            path: path.clone(),
+            source,
+        })?;
+        // SAFETY: the symbol is only read as a `PluginDeclaration` after its first field,
+        // the ABI version, says that it has this layout.
+        let declaration = unsafe {
+            let symbol = library
+                .get::<*const PluginDeclaration>(PLUGIN_SYMBOL.as_bytes())
+                .map_err(|source| PluginError::NotAPlugin {
+                    path: path.clone(),
+                    source,
+                })?;
+            let declaration: *const PluginDeclaration = *symbol;
+            let abi_version = declaration.cast::<u32>().read();
+            if abi_version != PLUGIN_ABI_VERSION {
+                return Err(PluginError::AbiMismatch {
+                    path,
+                    expected: PLUGIN_ABI_VERSION,
+                    found: abi_version,
+                });
+            }
+            &*declaration
+        };
+        if declaration.traits_version != TRAITS_VERSION {
+            return Err(PluginError::VersionMismatch {
+                path,
+                expected: TRAITS_VERSION,
+                found: declaration.traits_version.to_owned(),
+            });
+        }
+
+        let before: Vec<String> = self.kinds().map(String::from).collect();
+        (declaration.register)(self);
+        let new_kinds = self
+            .kinds()
+            .filter(|kind| !before.iter().any(|old| old == kind))
+            .map(String::from)
+            .collect();
+        // Keep the library mapped for the rest of the program, see above.
+        std::mem::forget(library);
+        Ok(LoadedPlugin { path, new_kinds })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    const PLUGIN_CRATE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/plugins/star_plugin");
+
+    // Builds the example plugin crate into its own target directory and returns the path
+    // of the shared library.
+    fn build_star_plugin() -> PathBuf {
+        let target_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target/plugins");
+        let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+        let status = Command::new(cargo)
+            .args(["build", "--quiet", "--manifest-path"])
+            .arg(Path::new(PLUGIN_CRATE).join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .expect("cargo runs");
+        assert!(status.success(), "building the star plugin failed");
+        target_dir.join("debug").join(format!(
+            "{}star_plugin{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        ))
+    }
+
+    #[test]
+    fn loads_shapes_from_a_plugin() {
+        let mut registry = ShapeRegistry::with_builtin_shapes();
+        let plugin = registry.load_plugin(build_star_plugin()).unwrap();
+
+        assert_eq!(plugin.new_kinds, vec!["star"]);
+        let star = registry.build("star", &[5.0, 2.0, 1.0]).unwrap();
+        assert_eq!(crate::json::shape_to_json(star.as_ref())["kind"], "star");
+        assert!(star.area() > 0.0);
+        assert!(star.clone() == star);
+        assert!(registry.build("star", &[2.0, 2.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn reports_files_that_are_not_plugins() {
+        let mut registry = ShapeRegistry::new();
+
+        assert!(matches!(
+            registry.load_plugin("/nonexistent/libnothing.so"),
+            Err(PluginError::Load { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn reports_libraries_without_a_declaration() {
+        // A shared library, but not one of ours.
+        let err = ShapeRegistry::new().load_plugin("libc.so.6").unwrap_err();
+        assert!(matches!(err, PluginError::NotAPlugin { .. }));
+        assert!(err.to_string().contains("is not a shape plugin"));
+    }
+}
@@ -0,0 +1,326 @@
+// A quadtree spatial index over positioned `Box<dyn Shape>` trait objects. Shapes are
+// filed by their bounding boxes: each node covers a square-ish region and, once it holds
+// too many shapes, splits into four quadrants. A shape is stored in the smallest node that
+// fully contains its bounding box, so range and nearest-neighbour queries only have to
+// look at the nodes around the area of interest instead of every shape.
+//
+// Shapes outside of the tree's bounds are accepted too; they stay in the root node and are
+// checked by every query.
+use crate::bounds::BoundingBox;
+use crate::collision::Outline;
+use crate::shapes::Vertex;
+use crate::Shape;
+
+// Nodes split once they hold more than this many shapes...
+const DEFAULT_NODE_CAPACITY: usize = 8;
+// ...unless they are already this deep.
+const DEFAULT_MAX_DEPTH: usize = 8;
+
+// Handle to a shape stored in a `QuadTree`, returned by `insert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ShapeId(usize);
+
+struct Entry {
+    shape: Box<dyn Shape>,
+    position: Vertex,
+    bbox: BoundingBox,
+    outline: Outline,
+}
+
+struct Node {
+    bounds: BoundingBox,
+    depth: usize,
+    items: Vec<ShapeId>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+fn fits(outer: &BoundingBox, inner: &BoundingBox) -> bool {
+    inner.min.0 >= outer.min.0
+        && inner.min.1 >= outer.min.1
+        && inner.max.0 <= outer.max.0
+        && inner.max.1 <= outer.max.1
+}
+
+// Overlap including shared edges, so a range query finds shapes touching the range.
+fn overlaps(a: &BoundingBox, b: &BoundingBox) -> bool {
+    a.min.0 <= b.max.0 && b.min.0 <= a.max.0 && a.min.1 <= b.max.1 && b.min.1 <= a.max.1
+}
+
+impl Node {
+    fn new(bounds: BoundingBox, depth: usize) -> Node {
+        Node {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    // Index of the quadrant that fully contains `bbox`, if any.
+    fn quadrant_for(&self, bbox: &BoundingBox) -> Option<usize> {
+        self.children
+            .as_ref()?
+            .iter()
+            .position(|child| fits(&child.bounds, bbox))
+    }
+
+    fn insert(
+        &mut self,
+        id: ShapeId,
+        entries: &[Option<Entry>],
+        capacity: usize,
+        max_depth: usize,
+    ) {
+        let bbox = entry(entries, id).bbox;
+        if let Some(quadrant) = self.quadrant_for(&bbox) {
+            let children = self.children.as_mut().expect("quadrant implies children");
+            children[quadrant].insert(id, entries, capacity, max_depth);
+            return;
+        }
+        self.items.push(id);
+        if self.children.is_none() && self.items.len() > capacity && self.depth < max_depth {
+            self.split(entries, capacity, max_depth);
+        }
+    }
+
+    fn split(&mut self, entries: &[Option<Entry>], capacity: usize, max_depth: usize) {
+        let BoundingBox { min, max } = self.bounds;
+        let mid = ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0);
+        let depth = self.depth + 1;
+        self.children = Some(Box::new([
+            Node::new(BoundingBox::new(min, mid), depth),
+            Node::new(BoundingBox::new((mid.0, min.1), (max.0, mid.1)), depth),
+            Node::new(BoundingBox::new((min.0, mid.1), (mid.0, max.1)), depth),
+            Node::new(BoundingBox::new(mid, max), depth),
+        ]));
+        for id in std::mem::take(&mut self.items) {
+            self.insert(id, entries, capacity, max_depth);
+        }
+    }
+
+    fn remove(&mut self, id: ShapeId, bbox: &BoundingBox) -> bool {
+        if let Some(index) = self.items.iter().position(|&item| item == id) {
+            self.items.swap_remove(index);
+            return true;
+        }
+        match (self.quadrant_for(bbox), self.children.as_mut()) {
+            (Some(quadrant), Some(children)) => children[quadrant].remove(id, bbox),
+            _ => false,
+        }
+    }
+
+    fn query(&self, range: &BoundingBox, entries: &[Option<Entry>], found: &mut Vec<ShapeId>) {
+        found.extend(
+            self.items
+                .iter()
+                .copied()
+                .filter(|&id| overlaps(&entry(entries, id).bbox, range)),
+        );
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                if overlaps(&child.bounds, range) {
+                    child.query(range, entries, found);
+                }
+            }
+        }
+    }
+
+    fn nearest(&self, point: Vertex, entries: &[Option<Entry>], best: &mut Option<(ShapeId, f64)>) {
+        for &id in &self.items {
+            let distance = entry(entries, id).outline.distance_to_point(point);
+            if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                *best = Some((id, distance));
+            }
+        }
+        let Some(children) = &self.children else {
+            return;
+        };
+        // Visit the closest quadrants first; they are the most likely to shrink `best`
+        // and let us skip the others.
+        let mut order: Vec<(f64, &Node)> = children
+            .iter()
+            .map(|child| (Outline::Rect(child.bounds).distance_to_point(point), child))
+            .collect();
+        order.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (distance, child) in order {
+            if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                child.nearest(point, entries, best);
+            }
+        }
+    }
+}
+
+fn entry(entries: &[Option<Entry>], id: ShapeId) -> &Entry {
+    entries[id.0]
+        .as_ref()
+        .expect("indexed shape ids are always live")
+}
+
+pub struct QuadTree {
+    root: Node,
+    // Stored shapes indexed by `ShapeId`. Removed shapes leave a `None` behind so ids of
+    // the remaining shapes stay valid.
+    entries: Vec<Option<Entry>>,
+    len: usize,
+    capacity: usize,
+    max_depth: usize,
+}
+
+impl QuadTree {
+    pub fn new(bounds: BoundingBox) -> QuadTree {
+        QuadTree::with_limits(bounds, DEFAULT_NODE_CAPACITY, DEFAULT_MAX_DEPTH)
+    }
+
+    pub fn with_limits(bounds: BoundingBox, node_capacity: usize, max_depth: usize) -> QuadTree {
+        QuadTree {
+            root: Node::new(bounds, 0),
+            entries: Vec::new(),
+            len: 0,
+            capacity: node_capacity.max(1),
+            max_depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Stores a shape with the top-left corner of its bounding box at `position`.
+    pub fn insert(&mut self, shape: Box<dyn Shape>, position: Vertex) -> ShapeId {
+        let bbox = shape.bounding_box().translate(position.0, position.1);
+        let outline = shape.outline().translate(position.0, position.1);
+        let id = ShapeId(self.entries.len());
+        self.entries.push(Some(Entry {
+            shape,
+            position,
+            bbox,
+            outline,
+        }));
+        self.len += 1;
+        self.root
+            .insert(id, &self.entries, self.capacity, self.max_depth);
+        id
+    }
+
+    // Takes a shape out of the tree, handing it back to the caller.
+    pub fn remove(&mut self, id: ShapeId) -> Option<Box<dyn Shape>> {
+        let bbox = self.entries.get(id.0)?.as_ref()?.bbox;
+        self.root.remove(id, &bbox);
+        self.len -= 1;
+        self.entries[id.0].take().map(|entry| entry.shape)
+    }
+
+    pub fn get(&self, id: ShapeId) -> Option<(&dyn Shape, Vertex)> {
+        let entry = self.entries.get(id.0)?.as_ref()?;
+        Some((entry.shape.as_ref(), entry.position))
+    }
+
+    // Shapes whose bounding boxes overlap (or touch) `range`, in no particular order.
+    pub fn query_range(&self, range: &BoundingBox) -> Vec<(ShapeId, &dyn Shape)> {
+        let mut found = Vec::new();
+        self.root.query(range, &self.entries, &mut found);
+        found
+            .into_iter()
+            .map(|id| (id, entry(&self.entries, id).shape.as_ref()))
+            .collect()
+    }
+
+    // Shapes containing the point, using their exact outlines.
+    pub fn query_point(&self, point: Vertex) -> Vec<(ShapeId, &dyn Shape)> {
+        let mut found = Vec::new();
+        self.root
+            .query(&BoundingBox::new(point, point), &self.entries, &mut found);
+        found
+            .into_iter()
+            .filter(|&id| entry(&self.entries, id).outline.contains_point(point))
+            .map(|id| (id, entry(&self.entries, id).shape.as_ref()))
+            .collect()
+    }
+
+    // The shape closest to `point` and its distance (0 for shapes containing the point).
+    pub fn nearest(&self, point: Vertex) -> Option<(ShapeId, &dyn Shape, f64)> {
+        let mut best = None;
+        self.root.nearest(point, &self.entries, &mut best);
+        best.map(|(id, distance)| (id, entry(&self.entries, id).shape.as_ref(), distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Circle, Square};
+
+    // A 10 x 10 grid of unit squares with their top-left corners at (2i, 2j), in a tree
+    // small enough to split several times.
+    fn grid() -> QuadTree {
+        let mut tree = QuadTree::with_limits(BoundingBox::from_size(20.0, 20.0), 2, 6);
+        for i in 0..10 {
+            for j in 0..10 {
+                tree.insert(
+                    Box::new(Square::new(1)),
+                    (f64::from(i) * 2.0, f64::from(j) * 2.0),
+                );
+            }
+        }
+        tree
+    }
+
+    // Ids of the shapes the brute-force way, for comparison with the tree.
+    fn brute_force_range(tree: &QuadTree, range: &BoundingBox) -> Vec<ShapeId> {
+        let mut ids: Vec<ShapeId> = (0..tree.entries.len())
+            .map(ShapeId)
+            .filter(|&id| tree.get(id).is_some())
+            .filter(|&id| overlaps(&entry(&tree.entries, id).bbox, range))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn range_query_matches_brute_force() {
+        let tree = grid();
+        let range = BoundingBox::new((3.5, 3.5), (8.0, 6.0));
+        let mut ids: Vec<ShapeId> = tree.query_range(&range).iter().map(|(id, _)| *id).collect();
+        ids.sort();
+
+        // Columns x = 4, 6, 8 and rows y = 4, 6.
+        assert_eq!(ids.len(), 6);
+        assert_eq!(ids, brute_force_range(&tree, &range));
+        assert!(tree.root.children.is_some());
+    }
+
+    #[test]
+    fn nearest_and_point_queries() {
+        let mut tree = grid();
+        let circle = tree.insert(Box::new(Circle::new(3.0)), (30.0, 30.0));
+
+        let (id, _, distance) = tree.nearest((5.5, 2.5)).unwrap();
+        assert_eq!(tree.get(id).unwrap().1, (6.0, 2.0));
+        assert_eq!(distance, 0.5);
+
+        // Outside the tree's bounds; the circle lives in the root node.
+        let (id, shape, distance) = tree.nearest((40.0, 33.0)).unwrap();
+        assert_eq!((id, distance), (circle, 4.0));
+        assert_eq!(shape.perimeter(), 6.0 * std::f64::consts::PI);
+
+        assert_eq!(tree.query_point((33.0, 33.0)).len(), 1);
+        assert!(tree.query_point((1.5, 1.5)).is_empty());
+    }
+
+    #[test]
+    fn removed_shapes_are_no_longer_found() {
+        let mut tree = grid();
+        let (id, _) = tree.query_point((0.5, 0.5))[0];
+
+        let shape = tree.remove(id).unwrap();
+        assert_eq!(shape.area(), 1.0);
+        assert_eq!(tree.len(), 99);
+        assert!(tree.query_point((0.5, 0.5)).is_empty());
+        assert!(tree.remove(id).is_none());
+        assert_eq!(tree.nearest((0.0, 0.0)).unwrap().2, 2.0);
+    }
+}
@@ -0,0 +1,232 @@
+// Runtime registry of shape kinds. Each kind is registered under a name together with a
+// constructor closure that builds a `Box<dyn Shape>` from a list of numeric parameters.
+// Crates that define their own shapes can register them here without touching this crate,
+// and callers can build shapes from names only known at runtime (e.g. read from a file).
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::error::check_dimension;
+use crate::{Circle, Rectangle, RegularPolygon, Shape, ShapeError, Square, Triangle};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    // No constructor was registered under this name.
+    UnknownKind(String),
+    WrongParameterCount { expected: usize, got: usize },
+    // A parameter had an unusable value, e.g. a fractional side for a `Square`.
+    InvalidParameter(String),
+    Shape(ShapeError),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::UnknownKind(name) => write!(f, "unknown shape kind `{}`", name),
+            BuildError::WrongParameterCount { expected, got } => {
+                write!(f, "expected {} parameters, got {}", expected, got)
+            }
+            BuildError::InvalidParameter(reason) => write!(f, "invalid parameter: {}", reason),
+            BuildError::Shape(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+impl From<ShapeError> for BuildError {
+    fn from(err: ShapeError) -> BuildError {
+        BuildError::Shape(err)
+    }
+}
+
+pub type ShapeConstructor = Box<dyn Fn(&[f64]) -> Result<Box<dyn Shape>, BuildError>>;
+
+// Checks that exactly `expected` parameters were given.
+pub fn expect_params(params: &[f64], expected: usize) -> Result<(), BuildError> {
+    if params.len() == expected {
+        Ok(())
+    } else {
+        Err(BuildError::WrongParameterCount {
+            expected,
+            got: params.len(),
+        })
+    }
+}
+
+// Converts a parameter to an `i32`, rejecting fractional and out of range values.
+pub fn integer_param(value: f64) -> Result<i32, BuildError> {
+    if value.fract() == 0.0 && value >= f64::from(i32::MIN) && value <= f64::from(i32::MAX) {
+        Ok(value as i32)
+    } else {
+        Err(BuildError::InvalidParameter(format!(
+            "{} is not a whole number that fits in an i32",
+            value
+        )))
+    }
+}
+
+#[derive(Default)]
+pub struct ShapeRegistry {
+    constructors: BTreeMap<String, ShapeConstructor>,
+}
+
+impl ShapeRegistry {
+    // An empty registry.
+    pub fn new() -> ShapeRegistry {
+        ShapeRegistry {
+            constructors: BTreeMap::new(),
+        }
+    }
+
+    // A registry with the shapes defined in this crate:
+    //   square <side>, rect/rectangle <length> <breadth>, circle <radius>,
+    //   regular_polygon <sides> <side length>, triangle <ax> <ay> <bx> <by> <cx> <cy>
+    pub fn with_builtin_shapes() -> ShapeRegistry {
+        let mut registry = ShapeRegistry::new();
+        registry.register("square", |params| {
+            expect_params(params, 1)?;
+            Ok(Box::new(Square::try_new(integer_param(params[0])?)?))
+        });
+        let rectangle = |params: &[f64]| -> Result<Box<dyn Shape>, BuildError> {
+            expect_params(params, 2)?;
+            Ok(Box::new(Rectangle::try_new(
+                integer_param(params[0])?,
+                integer_param(params[1])?,
+            )?))
+        };
+        registry.register("rect", rectangle);
+        registry.register("rectangle", rectangle);
+        registry.register("circle", |params| {
+            expect_params(params, 1)?;
+            Ok(Box::new(Circle::try_new(params[0])?))
+        });
+        registry.register("regular_polygon", |params| {
+            expect_params(params, 2)?;
+            let sides = check_dimension("sides", integer_param(params[0])?)?;
+            Ok(Box::new(RegularPolygon::try_new(sides as u32, params[1])?))
+        });
+        registry.register("triangle", |params| {
+            expect_params(params, 6)?;
+            Ok(Box::new(Triangle::try_new(
+                (params[0], params[1]),
+                (params[2], params[3]),
+                (params[4], params[5]),
+            )?))
+        });
+        registry
+    }
+
+    // Registers a constructor under `name`, replacing any previous one with that name.
+    pub fn register<F>(&mut self, name: &str, constructor: F)
+    where
+        F: Fn(&[f64]) -> Result<Box<dyn Shape>, BuildError> + 'static,
+    {
+        self.constructors
+            .insert(name.to_owned(), Box::new(constructor));
+    }
+
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.constructors.remove(name).is_some()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.constructors.contains_key(name)
+    }
+
+    // Names of all registered kinds in alphabetical order.
+    pub fn kinds(&self) -> impl Iterator<Item = &str> {
+        self.constructors.keys().map(String::as_str)
+    }
+
+    pub fn build(&self, name: &str, params: &[f64]) -> Result<Box<dyn Shape>, BuildError> {
+        let constructor = self
+            .constructors
+            .get(name)
+            .ok_or_else(|| BuildError::UnknownKind(name.to_owned()))?;
+        constructor(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_builtin_shapes_by_name() {
+        let registry = ShapeRegistry::with_builtin_shapes();
+        let square = registry.build("square", &[4.0]).unwrap();
+        let rectangle = registry.build("rect", &[8.0, 6.0]).unwrap();
+
+        assert_eq!(square.area(), 16.0);
+        assert_eq!(rectangle.perimeter(), 28.0);
+        assert!(registry.contains("circle"));
+    }
+
+    #[test]
+    fn reports_build_errors() {
+        let registry = ShapeRegistry::with_builtin_shapes();
+
+        assert_eq!(
+            registry.build("hexagon", &[1.0]).err(),
+            Some(BuildError::UnknownKind("hexagon".to_owned()))
+        );
+        assert_eq!(
+            registry.build("rect", &[1.0]).err(),
+            Some(BuildError::WrongParameterCount {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert!(matches!(
+            registry.build("square", &[2.5]),
+            Err(BuildError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            registry.build("square", &[-2.0]),
+            Err(BuildError::Shape(ShapeError::NegativeDimension { .. }))
+        ));
+    }
+
+    #[test]
+    fn rejects_negative_and_non_finite_parameters() {
+        let registry = ShapeRegistry::with_builtin_shapes();
+        let fails = |kind, params: &[f64]| {
+            matches!(registry.build(kind, params), Err(BuildError::Shape(_)))
+        };
+
+        assert!(fails("circle", &[-3.0]));
+        assert!(fails("circle", &[f64::NAN]));
+        assert!(fails("circle", &[f64::INFINITY]));
+        assert!(fails("regular_polygon", &[3.0, -2.0]));
+        assert!(fails("regular_polygon", &[-3.0, 2.0]));
+        assert!(fails("regular_polygon", &[2.0, 2.0]));
+        assert!(fails("regular_polygon", &[4.0, f64::NAN]));
+        assert!(fails("triangle", &[0.0, 0.0, 1.0, f64::NAN, 0.0, 1.0]));
+        assert!(fails("triangle", &[0.0, 0.0, 1.0, 0.0, f64::INFINITY, 1.0]));
+        assert!(registry
+            .build("triangle", &[0.0, 0.0, -1.0, 0.0, 0.0, -1.0])
+            .is_ok());
+    }
+
+    #[test]
+    fn custom_kinds_can_be_registered_and_listed() {
+        let mut registry = ShapeRegistry::new();
+        registry.register("unit_square", |params| {
+            expect_params(params, 0)?;
+            Ok(Box::new(Square::new(1)))
+        });
+        registry.register("disc", |params| {
+            expect_params(params, 1)?;
+            Ok(Box::new(Circle::try_new(params[0])?))
+        });
+
+        assert_eq!(
+            registry.kinds().collect::<Vec<_>>(),
+            vec!["disc", "unit_square"]
+        );
+        assert_eq!(registry.build("unit_square", &[]).unwrap().area(), 1.0);
+        assert!(registry.unregister("disc"));
+        assert!(!registry.contains("disc"));
+    }
+}
@@ -0,0 +1,618 @@
+// Shapes whose geometry is not whole numbers: circles, triangles, regular polygons and
+// arbitrary simple polygons. They implement the same `Shape` trait (and its supertraits)
+// as `Square` and `Rectangle`, which is possible because `area` and `perimeter` return
+// `f64`.
+//
+// Vertices are (x, y) pairs. When drawn or exported, a shape is positioned by the top-left
+// corner of its bounding box, so vertices may use any coordinate system.
+use std::f64::consts::PI;
+
+use serde_json::{Map, Value};
+
+use crate::capabilities::{Convex, Regular};
+use crate::collision::Outline;
+use crate::error::{self, ShapeError};
+use crate::json::vertex_to_json;
+use crate::visitor::ShapeVisitor;
+use crate::{Canvas, Draw, OtherTrait, Shape, SvgStyle, ToJson, ToSvg};
+
+pub type Vertex = (f64, f64);
+
+// Area of a simple polygon using the shoelace formula. The result is positive for
+// counter-clockwise vertices (in a y-up system) and negative for clockwise ones.
+pub fn signed_polygon_area(vertices: &[Vertex]) -> f64 {
+    let n = vertices.len();
+    let mut twice_area = 0.0;
+    for i in 0..n {
+        let (x1, y1) = vertices[i];
+        let (x2, y2) = vertices[(i + 1) % n];
+        twice_area += x1 * y2 - x2 * y1;
+    }
+    twice_area / 2.0
+}
+
+pub fn polygon_perimeter(vertices: &[Vertex]) -> f64 {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| {
+            let (x1, y1) = vertices[i];
+            let (x2, y2) = vertices[(i + 1) % n];
+            (x2 - x1).hypot(y2 - y1)
+        })
+        .sum()
+}
+
+// Even-odd ray casting test for whether `point` lies inside the polygon.
+pub fn polygon_contains(vertices: &[Vertex], point: Vertex) -> bool {
+    let (px, py) = point;
+    let n = vertices.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (x1, y1) = vertices[i];
+        let (x2, y2) = vertices[(i + 1) % n];
+        if (y1 > py) != (y2 > py) && px < x1 + (py - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// Smallest and largest coordinates of the vertices: ((min_x, min_y), (max_x, max_y)).
+fn bounds(vertices: &[Vertex]) -> (Vertex, Vertex) {
+    vertices.iter().fold(
+        (
+            (f64::INFINITY, f64::INFINITY),
+            (f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |((min_x, min_y), (max_x, max_y)), &(x, y)| {
+            ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+        },
+    )
+}
+
+// Size of the bounding box of the vertices, or (0, 0) if there are none.
+fn polygon_size(vertices: &[Vertex]) -> (f64, f64) {
+    if vertices.is_empty() {
+        return (0.0, 0.0);
+    }
+    let ((min_x, min_y), (max_x, max_y)) = bounds(vertices);
+    (max_x - min_x, max_y - min_y)
+}
+
+// Vertices moved so that their bounding box starts at (0, 0).
+fn normalized(vertices: &[Vertex]) -> Vec<Vertex> {
+    if vertices.is_empty() {
+        return Vec::new();
+    }
+    let ((min_x, min_y), _) = bounds(vertices);
+    vertices
+        .iter()
+        .map(|&(x, y)| (x - min_x, y - min_y))
+        .collect()
+}
+
+fn draw_polygon(vertices: &[Vertex], canvas: &mut Canvas) {
+    let local = normalized(vertices);
+    let (width, height) = polygon_size(&local);
+    canvas.draw_region(width.ceil() as i32, height.ceil() as i32, |x, y| {
+        polygon_contains(&local, (x, y))
+    });
+}
+
+fn polygon_svg_element(vertices: &[Vertex], x: f64, y: f64, style: &SvgStyle) -> String {
+    let points: Vec<String> = normalized(vertices)
+        .iter()
+        .map(|(vx, vy)| format!("{},{}", x + vx, y + vy))
+        .collect();
+    format!(
+        r#"<polygon points="{}"{}/>"#,
+        points.join(" "),
+        style.attributes()
+    )
+}
+
+// Circle struct and its associated funcs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle {
+    radius: f64,
+}
+
+impl Circle {
+    pub fn new(radius: f64) -> Circle {
+        Circle { radius }
+    }
+
+    // Validated constructor, rejects negative, NaN and infinite radii.
+    pub fn try_new(radius: f64) -> Result<Circle, ShapeError> {
+        Ok(Circle {
+            radius: error::check_length("radius", radius)?,
+        })
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Circle {
+            center: (self.radius, self.radius),
+            radius: self.radius,
+        }
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_circle(self);
+    }
+}
+
+impl Draw for Circle {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        let r = self.radius;
+        let diameter = (2.0 * r).ceil() as i32;
+        canvas.draw_region(diameter, diameter, |x, y| {
+            (x - r).powi(2) + (y - r).powi(2) <= r * r
+        });
+    }
+}
+
+impl OtherTrait for Circle {
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn is_regular(&self) -> bool {
+        true
+    }
+}
+
+impl Convex for Circle {}
+
+impl Regular for Circle {
+    fn circumradius(&self) -> f64 {
+        self.radius
+    }
+
+    fn inradius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl ToJson for Circle {
+    fn json_kind(&self) -> &'static str {
+        "circle"
+    }
+
+    fn json_fields(&self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        fields.insert("radius".to_owned(), self.radius.into());
+        fields
+    }
+}
+
+impl ToSvg for Circle {
+    fn svg_size(&self) -> (f64, f64) {
+        (2.0 * self.radius, 2.0 * self.radius)
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        format!(
+            r#"<circle cx="{}" cy="{}" r="{}"{}/>"#,
+            x + self.radius,
+            y + self.radius,
+            self.radius,
+            style.attributes()
+        )
+    }
+}
+
+// Triangle struct and its associated funcs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangle {
+    vertices: [Vertex; 3],
+}
+
+impl Triangle {
+    pub fn new(a: Vertex, b: Vertex, c: Vertex) -> Triangle {
+        Triangle {
+            vertices: [a, b, c],
+        }
+    }
+
+    // Validated constructor, rejects NaN and infinite coordinates.
+    pub fn try_new(a: Vertex, b: Vertex, c: Vertex) -> Result<Triangle, ShapeError> {
+        Ok(Triangle {
+            vertices: [
+                error::check_vertex(a)?,
+                error::check_vertex(b)?,
+                error::check_vertex(c)?,
+            ],
+        })
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        signed_polygon_area(&self.vertices).abs()
+    }
+
+    fn perimeter(&self) -> f64 {
+        polygon_perimeter(&self.vertices)
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(normalized(&self.vertices))
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_triangle(self);
+    }
+}
+
+impl Draw for Triangle {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        draw_polygon(&self.vertices, canvas);
+    }
+}
+
+impl OtherTrait for Triangle {
+    fn is_convex(&self) -> bool {
+        true
+    }
+}
+
+impl Convex for Triangle {}
+
+impl ToJson for Triangle {
+    fn json_kind(&self) -> &'static str {
+        "triangle"
+    }
+
+    fn json_fields(&self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        let vertices: Vec<Value> = self.vertices.iter().map(|&v| vertex_to_json(v)).collect();
+        fields.insert("vertices".to_owned(), vertices.into());
+        fields
+    }
+}
+
+impl ToSvg for Triangle {
+    fn svg_size(&self) -> (f64, f64) {
+        polygon_size(&self.vertices)
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        polygon_svg_element(&self.vertices, x, y, style)
+    }
+}
+
+// RegularPolygon struct and its associated funcs. A polygon with `sides` equal sides of
+// length `side_length`, e.g. a regular hexagon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegularPolygon {
+    sides: u32,
+    side_length: f64,
+}
+
+impl RegularPolygon {
+    pub fn new(sides: u32, side_length: f64) -> RegularPolygon {
+        RegularPolygon { sides, side_length }
+    }
+
+    // Validated constructor, rejects fewer than 3 sides and invalid side lengths.
+    pub fn try_new(sides: u32, side_length: f64) -> Result<RegularPolygon, ShapeError> {
+        if sides < 3 {
+            return Err(ShapeError::TooFewVertices {
+                minimum: 3,
+                got: sides as usize,
+            });
+        }
+        Ok(RegularPolygon {
+            sides,
+            side_length: error::check_length("side length", side_length)?,
+        })
+    }
+
+    pub fn sides(&self) -> u32 {
+        self.sides
+    }
+
+    pub fn side_length(&self) -> f64 {
+        self.side_length
+    }
+
+    // Distance from the centre to each vertex.
+    pub fn circumradius(&self) -> f64 {
+        self.side_length / (2.0 * (PI / f64::from(self.sides)).sin())
+    }
+
+    // Vertices around the centre (0, 0), the first one pointing straight up.
+    pub fn vertices(&self) -> Vec<Vertex> {
+        let radius = self.circumradius();
+        let n = f64::from(self.sides);
+        (0..self.sides)
+            .map(|i| {
+                let angle = 2.0 * PI * f64::from(i) / n - PI / 2.0;
+                (radius * angle.cos(), radius * angle.sin())
+            })
+            .collect()
+    }
+}
+
+impl Shape for RegularPolygon {
+    fn area(&self) -> f64 {
+        let n = f64::from(self.sides);
+        n * self.side_length * self.side_length / (4.0 * (PI / n).tan())
+    }
+
+    fn perimeter(&self) -> f64 {
+        f64::from(self.sides) * self.side_length
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(normalized(&self.vertices()))
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_regular_polygon(self);
+    }
+}
+
+impl Draw for RegularPolygon {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        draw_polygon(&self.vertices(), canvas);
+    }
+}
+
+impl OtherTrait for RegularPolygon {
+    fn is_convex(&self) -> bool {
+        true
+    }
+
+    fn is_regular(&self) -> bool {
+        true
+    }
+}
+
+impl Convex for RegularPolygon {}
+
+impl Regular for RegularPolygon {
+    fn circumradius(&self) -> f64 {
+        RegularPolygon::circumradius(self)
+    }
+
+    fn inradius(&self) -> f64 {
+        self.side_length / (2.0 * (PI / f64::from(self.sides)).tan())
+    }
+}
+
+impl ToJson for RegularPolygon {
+    fn json_kind(&self) -> &'static str {
+        "regular_polygon"
+    }
+
+    fn json_fields(&self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        fields.insert("sides".to_owned(), self.sides.into());
+        fields.insert("side_length".to_owned(), self.side_length.into());
+        fields
+    }
+}
+
+impl ToSvg for RegularPolygon {
+    fn svg_size(&self) -> (f64, f64) {
+        polygon_size(&self.vertices())
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        polygon_svg_element(&self.vertices(), x, y, style)
+    }
+}
+
+// Polygon struct and its associated funcs. Any simple (non self-intersecting) polygon
+// given by its vertices in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Vertex>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Vertex>) -> Polygon {
+        Polygon { vertices }
+    }
+
+    // Validated constructor, rejects fewer than 3 vertices and NaN or infinite
+    // coordinates.
+    pub fn try_new(vertices: Vec<Vertex>) -> Result<Polygon, ShapeError> {
+        if vertices.len() < 3 {
+            return Err(ShapeError::TooFewVertices {
+                minimum: 3,
+                got: vertices.len(),
+            });
+        }
+        for &vertex in &vertices {
+            error::check_vertex(vertex)?;
+        }
+        Ok(Polygon { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f64 {
+        signed_polygon_area(&self.vertices).abs()
+    }
+
+    fn perimeter(&self) -> f64 {
+        polygon_perimeter(&self.vertices)
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(normalized(&self.vertices))
+    }
+
+    fn accept(&self, visitor: &mut dyn ShapeVisitor) {
+        visitor.visit_polygon(self);
+    }
+}
+
+impl Draw for Polygon {
+    fn draw_object(&self, canvas: &mut Canvas) {
+        draw_polygon(&self.vertices, canvas);
+    }
+}
+
+impl OtherTrait for Polygon {}
+
+impl ToJson for Polygon {
+    fn json_kind(&self) -> &'static str {
+        "polygon"
+    }
+
+    fn json_fields(&self) -> Map<String, Value> {
+        let mut fields = Map::new();
+        let vertices: Vec<Value> = self.vertices.iter().map(|&v| vertex_to_json(v)).collect();
+        fields.insert("vertices".to_owned(), vertices.into());
+        fields
+    }
+}
+
+impl ToSvg for Polygon {
+    fn svg_size(&self) -> (f64, f64) {
+        polygon_size(&self.vertices)
+    }
+
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String {
+        polygon_svg_element(&self.vertices, x, y, style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DrawMode;
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn circle_measurements() {
+        let circle = Circle::new(2.0);
+
+        assert!(approx_eq(circle.area(), 4.0 * PI));
+        assert!(approx_eq(circle.perimeter(), 4.0 * PI));
+    }
+
+    #[test]
+    fn triangle_uses_shoelace_area() {
+        let triangle = Triangle::new((0.0, 0.0), (4.0, 0.0), (0.0, 3.0));
+
+        assert!(approx_eq(triangle.area(), 6.0));
+        assert!(approx_eq(triangle.perimeter(), 12.0));
+    }
+
+    #[test]
+    fn regular_polygon_matches_square_and_hexagon() {
+        let square = RegularPolygon::new(4, 3.0);
+        let hexagon = RegularPolygon::new(6, 2.0);
+
+        assert!(approx_eq(square.area(), 9.0));
+        assert!(approx_eq(square.perimeter(), 12.0));
+        assert!(approx_eq(hexagon.area(), 6.0 * 3f64.sqrt()));
+        assert!(approx_eq(
+            hexagon.area(),
+            signed_polygon_area(&hexagon.vertices()).abs()
+        ));
+    }
+
+    #[test]
+    fn polygon_area_ignores_winding_and_offset() {
+        let l_shape = vec![
+            (10.0, 10.0),
+            (10.0, 13.0),
+            (11.0, 13.0),
+            (11.0, 11.0),
+            (13.0, 11.0),
+            (13.0, 10.0),
+        ];
+        let reversed: Vec<Vertex> = l_shape.iter().rev().copied().collect();
+
+        assert!(approx_eq(Polygon::new(l_shape).area(), 5.0));
+        assert!(approx_eq(Polygon::new(reversed).area(), 5.0));
+        assert!(polygon_contains(
+            &[(0.0, 0.0), (2.0, 0.0), (0.0, 2.0)],
+            (0.5, 0.5)
+        ));
+        assert!(!polygon_contains(
+            &[(0.0, 0.0), (2.0, 0.0), (0.0, 2.0)],
+            (1.5, 1.5)
+        ));
+    }
+
+    #[test]
+    fn circle_and_triangle_rasterize() {
+        let mut canvas = Canvas::new(6, 4);
+        canvas.place(&Circle::new(2.0), 0, 0, DrawMode::Filled);
+        canvas.place(
+            &Triangle::new((0.0, 0.0), (2.0, 0.0), (0.0, 2.0)),
+            4,
+            0,
+            DrawMode::Filled,
+        );
+        let rows: Vec<String> = canvas.render().lines().map(String::from).collect();
+
+        assert_eq!(rows, vec![".##.#.", "####..", "####..", ".##..."]);
+    }
+
+    #[test]
+    fn validated_constructors_reject_invalid_shapes() {
+        assert!(Circle::try_new(0.0).is_ok());
+        assert_eq!(
+            Circle::try_new(-3.0),
+            Err(ShapeError::InvalidDimension {
+                dimension: "radius",
+                value: -3.0
+            })
+        );
+        assert!(Circle::try_new(f64::NAN).is_err());
+        assert!(Circle::try_new(f64::INFINITY).is_err());
+
+        assert_eq!(
+            RegularPolygon::try_new(0, 1.0),
+            Err(ShapeError::TooFewVertices { minimum: 3, got: 0 })
+        );
+        assert!(RegularPolygon::try_new(3, -2.0).is_err());
+        assert!(RegularPolygon::try_new(6, 2.0).is_ok());
+
+        let origin = (0.0, 0.0);
+        assert_eq!(
+            Triangle::try_new(origin, (1.0, f64::NEG_INFINITY), (0.0, 1.0)),
+            Err(ShapeError::NonFiniteCoordinate(f64::NEG_INFINITY))
+        );
+        assert!(Triangle::try_new(origin, (1.0, 0.0), (0.0, 1.0)).is_ok());
+
+        assert_eq!(
+            Polygon::try_new(vec![origin, (1.0, 1.0)]),
+            Err(ShapeError::TooFewVertices { minimum: 3, got: 2 })
+        );
+        assert!(Polygon::try_new(vec![origin, (1.0, 0.0), (f64::NAN, 1.0)]).is_err());
+        assert!(Polygon::try_new(vec![origin, (1.0, 0.0), (1.0, 1.0)]).is_ok());
+    }
+}
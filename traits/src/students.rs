@@ -0,0 +1,559 @@
+// A store of `Student` records. Every student added gets a unique `StudentId` that stays
+// the same while the record is updated and is never handed out again after removal.
+//
+// Besides the records themselves the registry keeps two ordered indexes, by lowercased
+// name and by age, so name prefix and age range lookups are range queries on a
+// `BTreeSet` instead of scans over all students.
+//
+// Records can be exported to and imported from CSV with an `id,name,age` header. Import
+// keeps going after a bad row: valid rows are added and every invalid one is reported
+// with its line number.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+use crate::Student;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StudentId(u32);
+
+impl StudentId {
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for StudentId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StudentError {
+    UnknownId(StudentId),
+    EmptyName,
+    NegativeAge(i32),
+    // Only possible when importing records with their ids.
+    DuplicateId(StudentId),
+    // Only possible when importing: the id belonged to a student that was removed.
+    RetiredId(StudentId),
+    // Every id up to `u32::MAX` has been handed out (or imported).
+    IdsExhausted,
+}
+
+impl fmt::Display for StudentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StudentError::UnknownId(id) => write!(f, "no student with id {}", id),
+            StudentError::EmptyName => write!(f, "student name is empty"),
+            StudentError::NegativeAge(age) => write!(f, "negative age {}", age),
+            StudentError::DuplicateId(id) => write!(f, "id {} is already taken", id),
+            StudentError::RetiredId(id) => {
+                write!(f, "id {} belonged to a removed student", id)
+            }
+            StudentError::IdsExhausted => write!(f, "no student ids left"),
+        }
+    }
+}
+
+impl Error for StudentError {}
+
+fn validate(student: &Student) -> Result<(), StudentError> {
+    if student.name.trim().is_empty() {
+        Err(StudentError::EmptyName)
+    } else if student.age < 0 {
+        Err(StudentError::NegativeAge(student.age))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvErrorKind {
+    // The first line isn't the `id,name,age` header.
+    MissingHeader,
+    WrongFieldCount { expected: usize, got: usize },
+    UnterminatedQuote,
+    InvalidId(String),
+    InvalidAge(String),
+    Student(StudentError),
+}
+
+// A CSV row that couldn't be imported, with its 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    pub line: usize,
+    pub kind: CsvErrorKind,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            CsvErrorKind::MissingHeader => write!(f, "expected the header `{}`", CSV_HEADER),
+            CsvErrorKind::WrongFieldCount { expected, got } => {
+                write!(f, "expected {} fields, got {}", expected, got)
+            }
+            CsvErrorKind::UnterminatedQuote => write!(f, "unterminated quoted field"),
+            CsvErrorKind::InvalidId(text) => write!(f, "invalid id `{}`", text),
+            CsvErrorKind::InvalidAge(text) => write!(f, "invalid age `{}`", text),
+            CsvErrorKind::Student(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for CsvError {}
+
+// Outcome of a CSV import: the ids of the added students and the rows that were skipped.
+#[derive(Debug, Default, PartialEq)]
+pub struct CsvImport {
+    pub imported: Vec<StudentId>,
+    pub errors: Vec<CsvError>,
+}
+
+pub const CSV_HEADER: &str = "id,name,age";
+
+// Quotes a field when it contains a comma, a quote or a line break.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+// Splits a CSV document into records, each with the line it starts on. Quoted fields may
+// contain commas, doubled quotes and line breaks.
+fn csv_records(csv: &str) -> Vec<(usize, Result<Vec<String>, CsvErrorKind>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let (mut line, mut record_line) = (1, 1);
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', false) if field.is_empty() => quoted = true,
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                fields.push(std::mem::take(&mut field));
+                records.push((record_line, Ok(std::mem::take(&mut fields))));
+                line += 1;
+                record_line = line;
+            }
+            (c, _) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if quoted {
+        records.push((record_line, Err(CsvErrorKind::UnterminatedQuote)));
+    } else if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((record_line, Ok(fields)));
+    }
+    records
+}
+
+#[derive(Debug)]
+pub struct StudentRegistry {
+    students: BTreeMap<StudentId, Student>,
+    by_name: BTreeSet<(String, StudentId)>,
+    by_age: BTreeSet<(i32, StudentId)>,
+    // The id `add` hands out next, `None` once `u32::MAX` has been used.
+    next_id: Option<u32>,
+    // Ids of removed students, which imports must not bring back.
+    retired: BTreeSet<StudentId>,
+}
+
+impl Default for StudentRegistry {
+    fn default() -> StudentRegistry {
+        StudentRegistry::new()
+    }
+}
+
+impl StudentRegistry {
+    pub fn new() -> StudentRegistry {
+        StudentRegistry {
+            students: BTreeMap::new(),
+            by_name: BTreeSet::new(),
+            by_age: BTreeSet::new(),
+            next_id: Some(0),
+            retired: BTreeSet::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.students.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.students.is_empty()
+    }
+
+    pub fn get(&self, id: StudentId) -> Option<&Student> {
+        self.students.get(&id)
+    }
+
+    // All students in id order, i.e. the order they were added in.
+    pub fn iter(&self) -> impl Iterator<Item = (StudentId, &Student)> {
+        self.students.iter().map(|(&id, student)| (id, student))
+    }
+
+    pub fn add(&mut self, student: Student) -> Result<StudentId, StudentError> {
+        validate(&student)?;
+        let id = StudentId(self.next_id.ok_or(StudentError::IdsExhausted)?);
+        self.insert(id, student);
+        Ok(id)
+    }
+
+    // Replaces the record of `id`, returning the previous one.
+    pub fn update(&mut self, id: StudentId, student: Student) -> Result<Student, StudentError> {
+        validate(&student)?;
+        let old = self.unlink(id).ok_or(StudentError::UnknownId(id))?;
+        self.insert(id, student);
+        Ok(old)
+    }
+
+    pub fn remove(&mut self, id: StudentId) -> Option<Student> {
+        let student = self.unlink(id)?;
+        self.retired.insert(id);
+        Some(student)
+    }
+
+    // Takes the record out of the map and the indexes, without retiring its id.
+    fn unlink(&mut self, id: StudentId) -> Option<Student> {
+        let student = self.students.remove(&id)?;
+        self.by_name.remove(&(student.name.to_lowercase(), id));
+        self.by_age.remove(&(student.age, id));
+        Some(student)
+    }
+
+    fn insert(&mut self, id: StudentId, student: Student) {
+        self.by_name.insert((student.name.to_lowercase(), id));
+        self.by_age.insert((student.age, id));
+        self.students.insert(id, student);
+        if self.next_id.is_some_and(|next| id.0 >= next) {
+            self.next_id = id.0.checked_add(1);
+        }
+    }
+
+    // Students whose name starts with `prefix`, ignoring case, sorted by name.
+    pub fn find_by_name_prefix(&self, prefix: &str) -> Vec<(StudentId, &Student)> {
+        let prefix = prefix.to_lowercase();
+        self.by_name
+            .range((prefix.clone(), StudentId(0))..)
+            .take_while(|(name, _)| name.starts_with(&prefix))
+            .map(|&(_, id)| (id, &self.students[&id]))
+            .collect()
+    }
+
+    // Students whose age is in `ages`, sorted by age.
+    pub fn find_by_age<R: RangeBounds<i32>>(&self, ages: R) -> Vec<(StudentId, &Student)> {
+        let start = match ages.start_bound() {
+            Bound::Included(&age) => Bound::Included((age, StudentId(0))),
+            Bound::Excluded(&age) => Bound::Excluded((age, StudentId(u32::MAX))),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        // Only the start goes into `range`, which panics on an end before the start; the
+        // end is checked while walking the index.
+        self.by_age
+            .range((start, Bound::Unbounded))
+            .take_while(|(age, _)| match ages.end_bound() {
+                Bound::Included(end) => age <= end,
+                Bound::Excluded(end) => age < end,
+                Bound::Unbounded => true,
+            })
+            .map(|&(_, id)| (id, &self.students[&id]))
+            .collect()
+    }
+
+    // Students in their derived `Ord` order: by name, then by age.
+    pub fn sorted(&self) -> Vec<&Student> {
+        let mut students: Vec<&Student> = self.students.values().collect();
+        students.sort();
+        students
+    }
+
+    // Groups of ids whose records are identical, found by hashing the records.
+    pub fn duplicates(&self) -> Vec<Vec<StudentId>> {
+        let mut groups: HashMap<&Student, Vec<StudentId>> = HashMap::new();
+        for (&id, student) in &self.students {
+            groups.entry(student).or_default().push(id);
+        }
+        let mut duplicates: Vec<Vec<StudentId>> =
+            groups.into_values().filter(|ids| ids.len() > 1).collect();
+        duplicates.sort();
+        duplicates
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        for (id, student) in self.iter() {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                id,
+                csv_field(&student.name),
+                student.age
+            ));
+        }
+        csv
+    }
+
+    // Adds the students of a CSV document written by `to_csv`. Rows keep their ids, so a
+    // row whose id is already in the registry is reported instead of being imported.
+    pub fn import_csv(&mut self, csv: &str) -> CsvImport {
+        let mut report = CsvImport::default();
+        let mut records = csv_records(csv).into_iter();
+        match records.next() {
+            Some((_, Ok(header))) if header.join(",").trim() == CSV_HEADER => {}
+            _ => {
+                report.errors.push(CsvError {
+                    line: 1,
+                    kind: CsvErrorKind::MissingHeader,
+                });
+                return report;
+            }
+        }
+        for (line, fields) in records {
+            let blank =
+                matches!(&fields, Ok(fields) if fields.len() == 1 && fields[0].trim().is_empty());
+            if blank {
+                continue;
+            }
+            match fields.and_then(|fields| self.import_row(&fields)) {
+                Ok(id) => report.imported.push(id),
+                Err(kind) => report.errors.push(CsvError { line, kind }),
+            }
+        }
+        report
+    }
+
+    fn import_row(&mut self, fields: &[String]) -> Result<StudentId, CsvErrorKind> {
+        let [id, name, age] = fields else {
+            return Err(CsvErrorKind::WrongFieldCount {
+                expected: 3,
+                got: fields.len(),
+            });
+        };
+        let id = id
+            .trim()
+            .parse()
+            .map(StudentId)
+            .map_err(|_| CsvErrorKind::InvalidId(id.clone()))?;
+        let age = age
+            .trim()
+            .parse()
+            .map_err(|_| CsvErrorKind::InvalidAge(age.clone()))?;
+        let student = Student {
+            name: name.clone(),
+            age,
+        };
+        validate(&student).map_err(CsvErrorKind::Student)?;
+        if self.students.contains_key(&id) {
+            return Err(CsvErrorKind::Student(StudentError::DuplicateId(id)));
+        }
+        if self.retired.contains(&id) {
+            return Err(CsvErrorKind::Student(StudentError::RetiredId(id)));
+        }
+        self.insert(id, student);
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(name: &str, age: i32) -> Student {
+        Student {
+            name: name.to_owned(),
+            age,
+        }
+    }
+
+    fn names(found: &[(StudentId, &Student)]) -> Vec<String> {
+        found
+            .iter()
+            .map(|(_, student)| student.name.clone())
+            .collect()
+    }
+
+    fn registry() -> StudentRegistry {
+        let mut registry = StudentRegistry::new();
+        for (name, age) in [("Alice", 21), ("albert", 19), ("Bob", 22), ("Alina", 25)] {
+            registry.add(student(name, age)).unwrap();
+        }
+        registry
+    }
+
+    #[test]
+    fn ids_stay_unique_across_updates_and_removals() {
+        let mut registry = registry();
+        let bob = registry.find_by_name_prefix("bob")[0].0;
+
+        let old = registry.update(bob, student("Robert", 23)).unwrap();
+        assert_eq!(old, student("Bob", 22));
+        assert_eq!(registry.get(bob), Some(&student("Robert", 23)));
+        assert!(registry.find_by_name_prefix("bob").is_empty());
+
+        assert_eq!(registry.remove(bob), Some(student("Robert", 23)));
+        let carol = registry.add(student("Carol", 20)).unwrap();
+        assert_ne!(carol, bob);
+        assert_eq!(
+            registry.update(bob, student("Bob", 22)),
+            Err(StudentError::UnknownId(bob))
+        );
+        assert_eq!(registry.add(student(" ", 20)), Err(StudentError::EmptyName));
+        assert_eq!(registry.len(), 4);
+    }
+
+    #[test]
+    fn finds_students_by_name_prefix_and_age_range() {
+        let registry = registry();
+
+        assert_eq!(
+            names(&registry.find_by_name_prefix("AL")),
+            vec!["albert", "Alice", "Alina"]
+        );
+        assert_eq!(
+            names(&registry.find_by_name_prefix("ali")),
+            vec!["Alice", "Alina"]
+        );
+        assert_eq!(names(&registry.find_by_age(20..=22)), vec!["Alice", "Bob"]);
+        assert_eq!(names(&registry.find_by_age(..21)), vec!["albert"]);
+        assert_eq!(registry.find_by_age(23..).len(), 1);
+        let (low, high) = (30, 20);
+        assert!(registry.find_by_age(low..high).is_empty());
+    }
+
+    #[test]
+    fn sorts_by_derived_ord_and_finds_duplicates() {
+        let mut registry = registry();
+        let again = registry.add(student("Bob", 22)).unwrap();
+        registry.add(student("Bob", 18)).unwrap();
+
+        let sorted: Vec<(&str, i32)> = registry
+            .sorted()
+            .iter()
+            .map(|s| (s.name.as_str(), s.age))
+            .collect();
+        // Uppercase letters sort before lowercase ones.
+        assert_eq!(
+            sorted,
+            vec![
+                ("Alice", 21),
+                ("Alina", 25),
+                ("Bob", 18),
+                ("Bob", 22),
+                ("Bob", 22),
+                ("albert", 19)
+            ]
+        );
+        assert_eq!(registry.duplicates(), vec![vec![StudentId(2), again]]);
+    }
+
+    #[test]
+    fn csv_round_trips_and_reports_bad_rows() {
+        let mut registry = registry();
+        registry.add(student("Smith, \"Jo\"", 30)).unwrap();
+        let csv = registry.to_csv();
+        assert!(csv.contains("4,\"Smith, \"\"Jo\"\"\",30\n"));
+
+        let mut copy = StudentRegistry::new();
+        let report = copy.import_csv(&csv);
+        assert!(report.errors.is_empty());
+        assert_eq!(copy.to_csv(), csv);
+
+        let report =
+            copy.import_csv("id,name,age\n9,Dan,x\n10,Eve\n0,Zed,30\n11,Fay,-1\n12,Gus,40\n");
+        assert_eq!(report.imported, vec![StudentId(12)]);
+        let errors: Vec<String> = report.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "line 2: invalid age `x`",
+                "line 3: expected 3 fields, got 2",
+                "line 4: id 0 is already taken",
+                "line 5: negative age -1",
+            ]
+        );
+        // New students continue after the largest imported id.
+        assert_eq!(copy.add(student("Hal", 50)), Ok(StudentId(13)));
+
+        assert_eq!(
+            copy.import_csv("name,age\n").errors[0].kind,
+            CsvErrorKind::MissingHeader
+        );
+    }
+
+    #[test]
+    fn names_with_line_breaks_round_trip() {
+        let mut registry = StudentRegistry::new();
+        registry.add(student("Ann\nLee", 20)).unwrap();
+        registry.add(student("Bo\r\nKim", 21)).unwrap();
+        registry.add(student("Cy", 22)).unwrap();
+        let csv = registry.to_csv();
+
+        let mut copy = StudentRegistry::new();
+        let report = copy.import_csv(&csv);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(copy.get(StudentId(0)), Some(&student("Ann\nLee", 20)));
+        assert_eq!(copy.get(StudentId(1)), Some(&student("Bo\r\nKim", 21)));
+        assert_eq!(copy.to_csv(), csv);
+
+        // Line numbers in errors still count physical lines.
+        let report = copy.import_csv("id,name,age\n7,\"Di\nPo\",x\n8,\"Ed");
+        let errors: Vec<String> = report.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "line 2: invalid age `x`",
+                "line 4: unterminated quoted field"
+            ]
+        );
+    }
+
+    #[test]
+    fn imports_cannot_bring_back_removed_ids() {
+        let mut registry = StudentRegistry::new();
+        let id = registry.add(student("Ann", 21)).unwrap();
+        let csv = registry.to_csv();
+        registry.remove(id);
+
+        let report = registry.import_csv(&csv);
+        assert!(report.imported.is_empty());
+        assert_eq!(
+            report.errors[0].kind,
+            CsvErrorKind::Student(StudentError::RetiredId(id))
+        );
+        assert!(registry.is_empty());
+        assert_ne!(registry.add(student("Ann", 21)), Ok(id));
+    }
+
+    #[test]
+    fn add_fails_once_ids_run_out() {
+        let mut registry = StudentRegistry::new();
+        let csv = format!("id,name,age\n{},Max,30\n", u32::MAX);
+        assert!(registry.import_csv(&csv).errors.is_empty());
+
+        assert_eq!(
+            registry.add(student("Next", 20)),
+            Err(StudentError::IdsExhausted)
+        );
+        assert_eq!(registry.len(), 1);
+        assert_eq!(names(&registry.find_by_name_prefix("")), vec!["Max"]);
+        assert_eq!(names(&registry.find_by_age(..)), vec!["Max"]);
+    }
+}
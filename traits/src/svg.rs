@@ -0,0 +1,188 @@
+// SVG export for shapes. Every `Shape` must also implement `ToSvg` (it is one of its
+// supertraits), which is the hook a shape type uses to supply its own SVG element. The
+// `SvgWriter` then collects positioned elements and wraps them into a complete document.
+use std::fmt::Write;
+
+use crate::Shape;
+
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+// Presentation attributes shared by every SVG element we emit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgStyle {
+    pub stroke: String,
+    pub fill: String,
+    pub stroke_width: f64,
+}
+
+impl SvgStyle {
+    pub fn new(stroke: &str, fill: &str, stroke_width: f64) -> SvgStyle {
+        SvgStyle {
+            stroke: stroke.to_owned(),
+            fill: fill.to_owned(),
+            stroke_width,
+        }
+    }
+
+    // Renders the style as ` stroke=".." fill=".." stroke-width=".."`, ready to be
+    // appended to an element's attribute list.
+    pub fn attributes(&self) -> String {
+        format!(
+            r#" stroke="{}" fill="{}" stroke-width="{}""#,
+            escape_attribute(&self.stroke),
+            escape_attribute(&self.fill),
+            self.stroke_width
+        )
+    }
+}
+
+impl Default for SvgStyle {
+    fn default() -> SvgStyle {
+        SvgStyle::new("black", "none", 1.0)
+    }
+}
+
+// Trait that lets a type describe itself as a single SVG element. New shape types
+// implement this to control how they are exported.
+pub trait ToSvg {
+    // Width and height of the element's bounding box in SVG user units.
+    fn svg_size(&self) -> (f64, f64);
+
+    // The SVG element for this object with its bounding box's top-left corner at (x, y).
+    fn svg_element(&self, x: f64, y: f64, style: &SvgStyle) -> String;
+}
+
+// Escapes the characters that are not allowed to appear literally inside a
+// double-quoted XML attribute value.
+pub fn escape_attribute(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Collects positioned shapes and produces an SVG document. The document grows to fit
+// every shape added, unless an explicit size was given.
+pub struct SvgWriter {
+    elements: Vec<String>,
+    width: f64,
+    height: f64,
+    fixed_size: bool,
+}
+
+impl SvgWriter {
+    pub fn new() -> SvgWriter {
+        SvgWriter {
+            elements: Vec::new(),
+            width: 0.0,
+            height: 0.0,
+            fixed_size: false,
+        }
+    }
+
+    pub fn with_size(width: f64, height: f64) -> SvgWriter {
+        SvgWriter {
+            elements: Vec::new(),
+            width,
+            height,
+            fixed_size: true,
+        }
+    }
+
+    pub fn add(&mut self, shape: &dyn Shape, x: f64, y: f64, style: &SvgStyle) -> &mut Self {
+        self.elements.push(shape.svg_element(x, y, style));
+        if !self.fixed_size {
+            // Leave room for half of the stroke, which is painted outside the geometry.
+            let (w, h) = shape.svg_size();
+            let margin = style.stroke_width / 2.0;
+            self.width = self.width.max(x + w + margin);
+            self.height = self.height.max(y + h + margin);
+        }
+        self
+    }
+
+    pub fn finish(&self) -> String {
+        let mut doc = String::new();
+        let _ = writeln!(
+            doc,
+            r#"<svg xmlns="{}" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            SVG_NAMESPACE,
+            w = self.width,
+            h = self.height
+        );
+        for element in &self.elements {
+            let _ = writeln!(doc, "  {}", element);
+        }
+        doc.push_str("</svg>\n");
+        doc
+    }
+}
+
+impl Default for SvgWriter {
+    fn default() -> SvgWriter {
+        SvgWriter::new()
+    }
+}
+
+// Exports a single shape placed at the document's origin.
+pub fn shape_to_svg(shape: &dyn Shape, style: &SvgStyle) -> String {
+    let mut writer = SvgWriter::new();
+    writer.add(shape, 0.0, 0.0, style);
+    writer.finish()
+}
+
+// Exports a list of shapes laid out left to right, `gap` units apart.
+pub fn scene_to_svg(shapes: &[Box<dyn Shape>], gap: f64, style: &SvgStyle) -> String {
+    let mut writer = SvgWriter::new();
+    let mut x = 0.0;
+    for shape in shapes {
+        writer.add(shape.as_ref(), x, 0.0, style);
+        x += shape.svg_size().0 + gap;
+    }
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rectangle, Square};
+
+    #[test]
+    fn single_shape_document() {
+        let svg = shape_to_svg(&Square::new(4), &SvgStyle::new("red", "blue", 2.0));
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="5""#));
+        assert!(svg.contains(
+            r#"<rect x="0" y="0" width="4" height="4" stroke="red" fill="blue" stroke-width="2"/>"#
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn scene_places_shapes_side_by_side() {
+        let shapes: Vec<Box<dyn Shape>> =
+            vec![Box::new(Square::new(2)), Box::new(Rectangle::new(8, 6))];
+        let svg = scene_to_svg(&shapes, 1.0, &SvgStyle::default());
+
+        assert!(svg.contains(r#"<rect x="0" y="0" width="2" height="2""#));
+        assert!(svg.contains(r#"<rect x="3" y="0" width="8" height="6""#));
+        assert!(svg.contains(r#"viewBox="0 0 11.5 6.5""#));
+    }
+
+    #[test]
+    fn attribute_values_are_escaped() {
+        let style = SvgStyle::new(r#"a"b"#, "<none>", 1.0);
+
+        assert_eq!(
+            style.attributes(),
+            r#" stroke="a&quot;b" fill="&lt;none&gt;" stroke-width="1""#
+        );
+    }
+}
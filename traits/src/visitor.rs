@@ -0,0 +1,301 @@
+// The visitor pattern over shapes. Every shape implements `Shape::accept` once, calling the
+// `visit_*` method for its own type. New behaviour (an exporter, an analyzer...) can then be
+// written as a single `ShapeVisitor` implementation, with one method per shape type in one
+// place, instead of a new trait implemented on every shape type.
+//
+// The other side of the trade-off: adding a shape type means adding a method here, and
+// every visitor has to handle it.
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::shapes::Vertex;
+use crate::{
+    Circle, Group, Polygon, Rectangle, RegularPolygon, Shape, Square, Transform, Triangle,
+};
+
+pub trait ShapeVisitor {
+    fn visit_square(&mut self, square: &Square);
+    fn visit_rectangle(&mut self, rectangle: &Rectangle);
+    fn visit_circle(&mut self, circle: &Circle);
+    fn visit_triangle(&mut self, triangle: &Triangle);
+    fn visit_regular_polygon(&mut self, polygon: &RegularPolygon);
+    fn visit_polygon(&mut self, polygon: &Polygon);
+
+    // Groups visit their children by default. Visitors that care about the transforms
+    // override this and walk `group.children()` themselves.
+    fn visit_group(&mut self, group: &Group) {
+        for (child, _) in group.children() {
+            child.accept(self.as_dyn());
+        }
+    }
+
+    // Shapes defined outside of this crate (e.g. loaded from a plugin) have no method of
+    // their own above and call this one instead. Ignored by default.
+    fn visit_other(&mut self, _shape: &dyn Shape) {}
+
+    // Needed by the default `visit_group` to hand `self` on to the children as a trait
+    // object. Implementors just return `self`.
+    fn as_dyn(&mut self) -> &mut dyn ShapeVisitor;
+}
+
+// Visits every shape of a scene in order.
+pub fn visit_all(shapes: &[Box<dyn Shape>], visitor: &mut dyn ShapeVisitor) {
+    for shape in shapes {
+        shape.accept(visitor);
+    }
+}
+
+// Exporter writing one line per shape, in a notation close to the scene file format.
+// Group children are indented below their group, each with its transform.
+#[derive(Default)]
+pub struct TextExporter {
+    output: String,
+    depth: usize,
+}
+
+fn format_vertices(vertices: &[Vertex]) -> String {
+    let points: Vec<String> = vertices
+        .iter()
+        .map(|(x, y)| format!("({}, {})", x, y))
+        .collect();
+    points.join(" ")
+}
+
+impl TextExporter {
+    pub fn new() -> TextExporter {
+        TextExporter::default()
+    }
+
+    pub fn export(shapes: &[Box<dyn Shape>]) -> String {
+        let mut exporter = TextExporter::new();
+        visit_all(shapes, &mut exporter);
+        exporter.finish()
+    }
+
+    pub fn finish(self) -> String {
+        self.output
+    }
+
+    fn line(&mut self, text: &str) {
+        self.output.push_str(&"  ".repeat(self.depth));
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+}
+
+impl ShapeVisitor for TextExporter {
+    fn visit_square(&mut self, square: &Square) {
+        self.line(&format!("square {}", square.side()));
+    }
+
+    fn visit_rectangle(&mut self, rectangle: &Rectangle) {
+        self.line(&format!(
+            "rect {}x{}",
+            rectangle.length(),
+            rectangle.breadth()
+        ));
+    }
+
+    fn visit_circle(&mut self, circle: &Circle) {
+        self.line(&format!("circle {}", circle.radius()));
+    }
+
+    fn visit_triangle(&mut self, triangle: &Triangle) {
+        self.line(&format!(
+            "triangle {}",
+            format_vertices(triangle.vertices())
+        ));
+    }
+
+    fn visit_regular_polygon(&mut self, polygon: &RegularPolygon) {
+        self.line(&format!(
+            "regular_polygon {} sides of {}",
+            polygon.sides(),
+            polygon.side_length()
+        ));
+    }
+
+    fn visit_polygon(&mut self, polygon: &Polygon) {
+        self.line(&format!("polygon {}", format_vertices(polygon.vertices())));
+    }
+
+    fn visit_group(&mut self, group: &Group) {
+        self.line(&format!("group of {}", group.len()));
+        self.depth += 1;
+        for (child, transform) in group.children() {
+            let Transform {
+                translation: (x, y),
+                rotation,
+                scale,
+            } = *transform;
+            self.line(&format!(
+                "at ({}, {}) rotated {}° scaled {}:",
+                x,
+                y,
+                rotation.to_degrees(),
+                scale
+            ));
+            self.depth += 1;
+            child.accept(self);
+            self.depth -= 1;
+        }
+        self.depth -= 1;
+    }
+
+    // Falls back to the shape's JSON fields, which every shape has.
+    fn visit_other(&mut self, shape: &dyn Shape) {
+        self.line(&format!(
+            "{} {}",
+            shape.json_kind(),
+            Value::Object(shape.json_fields())
+        ));
+    }
+
+    fn as_dyn(&mut self) -> &mut dyn ShapeVisitor {
+        self
+    }
+}
+
+// Analyzer counting the shapes of a scene by kind. Groups are counted themselves and their
+// children are counted too, recursively.
+#[derive(Debug, Default, PartialEq)]
+pub struct ShapeStats {
+    pub counts: BTreeMap<&'static str, usize>,
+    // Areas and perimeters of the top-level shapes, so that children of groups count
+    // with their group's scale.
+    pub total_area: f64,
+    pub total_perimeter: f64,
+}
+
+impl ShapeStats {
+    pub fn of(shapes: &[Box<dyn Shape>]) -> ShapeStats {
+        let mut stats = ShapeStats::default();
+        for shape in shapes {
+            stats.total_area += shape.area();
+            stats.total_perimeter += shape.perimeter();
+            shape.accept(&mut stats);
+        }
+        stats
+    }
+
+    pub fn count(&self, kind: &str) -> usize {
+        self.counts.get(kind).copied().unwrap_or(0)
+    }
+
+    fn add(&mut self, kind: &'static str) {
+        *self.counts.entry(kind).or_insert(0) += 1;
+    }
+}
+
+impl ShapeVisitor for ShapeStats {
+    fn visit_square(&mut self, _: &Square) {
+        self.add("square");
+    }
+
+    fn visit_rectangle(&mut self, _: &Rectangle) {
+        self.add("rectangle");
+    }
+
+    fn visit_circle(&mut self, _: &Circle) {
+        self.add("circle");
+    }
+
+    fn visit_triangle(&mut self, _: &Triangle) {
+        self.add("triangle");
+    }
+
+    fn visit_regular_polygon(&mut self, _: &RegularPolygon) {
+        self.add("regular_polygon");
+    }
+
+    fn visit_polygon(&mut self, _: &Polygon) {
+        self.add("polygon");
+    }
+
+    fn visit_group(&mut self, group: &Group) {
+        self.add("group");
+        for (child, _) in group.children() {
+            child.accept(self);
+        }
+    }
+
+    fn visit_other(&mut self, shape: &dyn Shape) {
+        self.add(shape.json_kind());
+    }
+
+    fn as_dyn(&mut self) -> &mut dyn ShapeVisitor {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnyShape;
+
+    fn scene() -> Vec<Box<dyn Shape>> {
+        vec![
+            Box::new(Square::new(4)),
+            Box::new(Rectangle::new(8, 6)),
+            Box::new(
+                Group::new()
+                    .with(Box::new(Square::new(1)), Transform::identity())
+                    .with(
+                        Box::new(Circle::new(1.5)),
+                        Transform::translate(2.0, 0.0).with_scale(2.0).unwrap(),
+                    ),
+            ),
+        ]
+    }
+
+    #[test]
+    fn text_exporter_writes_every_shape() {
+        assert_eq!(
+            TextExporter::export(&scene()),
+            "square 4\n\
+             rect 8x6\n\
+             group of 2\n  \
+               at (0, 0) rotated 0° scaled 1:\n    \
+                 square 1\n  \
+               at (2, 0) rotated 0° scaled 2:\n    \
+                 circle 1.5\n"
+        );
+    }
+
+    #[test]
+    fn stats_count_shapes_inside_groups() {
+        let stats = ShapeStats::of(&scene());
+
+        assert_eq!(stats.count("square"), 2);
+        assert_eq!(stats.count("group"), 1);
+        assert_eq!(stats.count("triangle"), 0);
+        assert_eq!(stats.total_area, 16.0 + 48.0 + scene()[2].area());
+    }
+
+    // Only `visit_square` does anything, the group is walked by the default method.
+    struct SquareSides(Vec<i32>);
+
+    impl ShapeVisitor for SquareSides {
+        fn visit_square(&mut self, square: &Square) {
+            self.0.push(square.side());
+        }
+        fn visit_rectangle(&mut self, _: &Rectangle) {}
+        fn visit_circle(&mut self, _: &Circle) {}
+        fn visit_triangle(&mut self, _: &Triangle) {}
+        fn visit_regular_polygon(&mut self, _: &RegularPolygon) {}
+        fn visit_polygon(&mut self, _: &Polygon) {}
+        fn as_dyn(&mut self) -> &mut dyn ShapeVisitor {
+            self
+        }
+    }
+
+    #[test]
+    fn default_group_visit_reaches_children_and_enum_shapes_forward() {
+        let mut sides = SquareSides(Vec::new());
+        visit_all(&scene(), &mut sides);
+        AnyShape::from(Square::new(7)).accept(&mut sides);
+
+        assert_eq!(sides.0, vec![4, 1, 7]);
+    }
+}